@@ -1,15 +1,399 @@
-use derive_utils::quick_derive;
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, Lit, LitStr};
 
-#[proc_macro_derive(IntoApiError)]
-pub fn derive_iterator(input: TokenStream) -> TokenStream {
-    quick_derive! {
-        input,
-        // trait path
-        ::http_api_problem::IntoApiError,
-        // trait definition
-        pub trait IntoApiError {
-            fn into_api_error(self) -> ::http_api_problem::ApiError;
+/// Derives `IntoApiError`.
+///
+/// Structs and enum variants annotated with `#[api_error(...)]` get an
+/// `ApiError` built from the given attributes:
+///
+/// * `status`: The status code as an integer literal or an expression
+///   evaluating to a `StatusCode`. Defaults to `500`.
+/// * `title`, `message`, `type_url` and `instance`: Format strings which
+///   may refer to the fields by name (`{id}`) or index (`{0}`).
+///
+/// Fields can be annotated with
+///
+/// * `#[source]` or `#[api_error(source)]` to become the source of the `ApiError`
+/// * `#[api_error(field)]` or `#[api_error(field = "name")]` to be added as a
+///   field of the `ApiError`
+///
+/// Enum variants without an `#[api_error(...)]` attribute must have exactly
+/// one field which implements `IntoApiError` itself.
+#[proc_macro_derive(IntoApiError, attributes(api_error, source))]
+pub fn derive_into_api_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = ErrorAttrs::from_attrs(&input.attrs)?;
+            let (pattern, bindings) = destructure(quote!(Self), &data.fields)?;
+            let build = build_error(attrs, &data.fields, &bindings, input.ident.span())?;
+            quote! {
+                let #pattern = self;
+                #build
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let attrs = ErrorAttrs::from_attrs(&variant.attrs)?;
+                let (pattern, bindings) = destructure(quote!(Self::#ident), &variant.fields)?;
+                let build = build_error(attrs, &variant.fields, &bindings, variant.span())?;
+                arms.push(quote! {
+                    #pattern => { #build }
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "IntoApiError can not be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::http_api_problem::IntoApiError for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn into_api_error(self) -> ::http_api_problem::ApiError {
+                #body
+            }
+        }
+    })
+}
+
+#[derive(Default)]
+struct ErrorAttrs {
+    present: bool,
+    status: Option<Expr>,
+    title: Option<LitStr>,
+    message: Option<LitStr>,
+    type_url: Option<LitStr>,
+    instance: Option<LitStr>,
+}
+
+impl ErrorAttrs {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = ErrorAttrs::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("api_error")) {
+            parsed.present = true;
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(|i| i.to_string())
+                    .unwrap_or_default();
+                match key.as_str() {
+                    "status" => parsed.status = Some(meta.value()?.parse()?),
+                    "title" => parsed.title = Some(meta.value()?.parse()?),
+                    "message" => parsed.message = Some(meta.value()?.parse()?),
+                    "type_url" => parsed.type_url = Some(meta.value()?.parse()?),
+                    "instance" => parsed.instance = Some(meta.value()?.parse()?),
+                    _ => return Err(meta.error("unknown api_error attribute")),
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    source: bool,
+    field: Option<String>,
+}
+
+impl FieldAttrs {
+    fn from_field(field: &syn::Field, index: usize) -> syn::Result<Self> {
+        let mut parsed = FieldAttrs::default();
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("source") {
+                parsed.source = true;
+            } else if attr.path().is_ident("api_error") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("source") {
+                        parsed.source = true;
+                    } else if meta.path.is_ident("field") {
+                        if meta.input.peek(syn::Token![=]) {
+                            let name: LitStr = meta.value()?.parse()?;
+                            parsed.field = Some(name.value());
+                        } else {
+                            match field.ident.as_ref() {
+                                Some(ident) => parsed.field = Some(ident.to_string()),
+                                None => {
+                                    return Err(meta.error(format!(
+                                        "field {} has no name, use `field = \"name\"`",
+                                        index
+                                    )))
+                                }
+                            }
+                        }
+                    } else {
+                        return Err(meta.error("unknown api_error attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Creates a pattern binding all fields and returns the names of the bindings
+fn destructure(
+    path: TokenStream2,
+    fields: &Fields,
+) -> syn::Result<(TokenStream2, Vec<syn::Ident>)> {
+    match fields {
+        Fields::Named(named) => {
+            let bindings: Vec<_> = named
+                .named
+                .iter()
+                .map(|f| f.ident.clone().unwrap())
+                .collect();
+            Ok((quote!(#path { #(#bindings),* }), bindings))
         }
+        Fields::Unnamed(unnamed) => {
+            let bindings: Vec<_> = (0..unnamed.unnamed.len())
+                .map(|i| format_ident!("_{}", i))
+                .collect();
+            Ok((quote!(#path( #(#bindings),* )), bindings))
+        }
+        Fields::Unit => Ok((path, Vec::new())),
     }
 }
+
+fn build_error(
+    attrs: ErrorAttrs,
+    fields: &Fields,
+    bindings: &[syn::Ident],
+    span: Span,
+) -> syn::Result<TokenStream2> {
+    if !attrs.present {
+        return match bindings {
+            [inner] => Ok(quote! {
+                ::http_api_problem::IntoApiError::into_api_error(#inner)
+            }),
+            _ => Err(syn::Error::new(
+                span,
+                "expected an #[api_error(...)] attribute or exactly one field implementing IntoApiError",
+            )),
+        };
+    }
+
+    let status = match attrs.status {
+        Some(Expr::Lit(lit)) => match &lit.lit {
+            Lit::Int(int) => {
+                let code: u16 = int.base10_parse()?;
+                if !(100..=999).contains(&code) {
+                    return Err(syn::Error::new(int.span(), "invalid status code"));
+                }
+                quote!(::http_api_problem::StatusCode::from_u16(#code).unwrap())
+            }
+            _ => return Err(syn::Error::new(lit.span(), "expected a status code")),
+        },
+        Some(expr) => quote!({
+            let status: ::http_api_problem::StatusCode = #expr;
+            status
+        }),
+        None => quote!(::http_api_problem::StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let mut steps = Vec::new();
+    for (method, value) in [
+        ("title", attrs.title),
+        ("message", attrs.message),
+        ("type_url", attrs.type_url),
+        ("instance", attrs.instance),
+    ] {
+        if let Some(value) = value {
+            let method = format_ident!("{}", method);
+            let formatted = format_with_fields(&value)?;
+            steps.push(quote!(builder = builder.#method(#formatted);));
+        }
+    }
+
+    let mut source = None;
+    for ((index, field), binding) in fields.iter().enumerate().zip(bindings) {
+        let field_attrs = FieldAttrs::from_field(field, index)?;
+        if let Some(name) = field_attrs.field {
+            steps.push(quote!(builder = builder.field(#name, &#binding);));
+        }
+        if field_attrs.source {
+            if source.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only one field can be the source",
+                ));
+            }
+            source = Some(binding);
+        }
+    }
+
+    // The source is moved into the builder last since it might
+    // also be used for formatting.
+    if let Some(source) = source {
+        steps.push(quote!(builder = builder.source(#source);));
+    }
+
+    Ok(quote! {
+        let mut builder = ::http_api_problem::ApiError::builder(#status);
+        #(#steps)*
+        builder.finish()
+    })
+}
+
+/// Creates a `format!` invocation for a format string which refers to
+/// fields by name or index.
+///
+/// Indexes are rewritten to the names of the bindings of tuple fields (`{0}` becomes `{_0}`).
+fn format_with_fields(format: &LitStr) -> syn::Result<TokenStream2> {
+    let value = format.value();
+    let mut rewritten = String::with_capacity(value.len());
+    let mut args: Vec<String> = Vec::new();
+
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        rewritten.push(c);
+        match c {
+            '{' if chars.peek() == Some(&'{') => rewritten.push(chars.next().unwrap()),
+            '}' if chars.peek() == Some(&'}') => rewritten.push(chars.next().unwrap()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(next) => placeholder.push(next),
+                        None => {
+                            return Err(syn::Error::new(
+                                format.span(),
+                                "unterminated `{` in format string, use `{{` for a literal `{`",
+                            ))
+                        }
+                    }
+                }
+                let (arg, spec) = match placeholder.split_once(':') {
+                    Some((arg, spec)) => (arg.trim(), Some(spec)),
+                    None => (placeholder.trim(), None),
+                };
+                if arg.is_empty() {
+                    return Err(syn::Error::new(
+                        format.span(),
+                        "use `{name}` or `{0}` to refer to fields",
+                    ));
+                }
+                let arg = if arg.chars().all(|c| c.is_ascii_digit()) {
+                    format!(
+                        "_{}",
+                        arg.parse::<usize>()
+                            .map_err(|err| syn::Error::new(format.span(), err))?
+                    )
+                } else if !arg.starts_with("r#") && syn::parse_str::<syn::Ident>(arg).is_ok() {
+                    arg.to_string()
+                } else {
+                    return Err(syn::Error::new(
+                        format.span(),
+                        format!(
+                            "`{{{}}}` does not refer to a field, use `{{name}}` or `{{0}}`",
+                            placeholder
+                        ),
+                    ));
+                };
+                if let Some(spec) = spec {
+                    if !is_format_spec(spec) {
+                        return Err(syn::Error::new(
+                            format.span(),
+                            format!("invalid format spec `{}` in `{{{}}}`", spec, placeholder),
+                        ));
+                    }
+                }
+                rewritten.push_str(&arg);
+                if let Some(spec) = spec {
+                    rewritten.push(':');
+                    rewritten.push_str(spec);
+                }
+                rewritten.push('}');
+                if !args.contains(&arg) {
+                    args.push(arg);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if args.is_empty() {
+        return Ok(quote!(#format));
+    }
+
+    let rewritten = LitStr::new(&rewritten, format.span());
+    let args = args.iter().map(|a| format_ident!("{}", a));
+
+    Ok(quote!(::std::format!(#rewritten, #(#args = #args),*)))
+}
+
+/// Checks the spec of a placeholder (the part after the `:`) as described in
+/// the documentation of `std::fmt`.
+///
+/// Widths and precisions referring to arguments (`name$` and `*`) are not
+/// supported since the fields are passed as named arguments.
+fn is_format_spec(spec: &str) -> bool {
+    let is_align = |c: char| c == '<' || c == '^' || c == '>';
+    let chars: Vec<char> = spec.chars().collect();
+    let mut rest = &chars[..];
+
+    match rest {
+        [_, align, ..] if is_align(*align) => rest = &rest[2..],
+        [align, ..] if is_align(*align) => rest = &rest[1..],
+        _ => {}
+    }
+    for flags in ["+-", "#", "0"] {
+        if let [c, tail @ ..] = rest {
+            if flags.contains(*c) {
+                rest = tail;
+            }
+        }
+    }
+
+    let digits = |rest: &[char]| rest.iter().take_while(|c| c.is_ascii_digit()).count();
+    rest = &rest[digits(rest)..];
+    if let ['.', tail @ ..] = rest {
+        let precision = digits(tail);
+        if precision == 0 {
+            return false;
+        }
+        rest = &tail[precision..];
+    }
+
+    let ty: String = rest.iter().collect();
+    matches!(
+        ty.as_str(),
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p"
+    )
+}
+
+#[cfg(test)]
+mod test;
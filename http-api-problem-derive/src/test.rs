@@ -0,0 +1,22 @@
+use super::*;
+
+mod format_specs {
+    use super::*;
+
+    #[test]
+    fn valid_specs() {
+        for spec in [
+            "", "?", "#?", "x?", "X?", "x", "#x", "08.3", ">8", "*^8", "-^8", "+", "+.2e", ".3",
+            "<", "#010b", "p",
+        ] {
+            assert!(is_format_spec(spec), "{:?} is valid", spec);
+        }
+    }
+
+    #[test]
+    fn invalid_specs() {
+        for spec in ["?x", "y", ".", "*", "width$", ".*", "8.3.2", ">>>", "??"] {
+            assert!(!is_format_spec(spec), "{:?} is invalid", spec);
+        }
+    }
+}
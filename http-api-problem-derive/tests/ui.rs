@@ -0,0 +1,4 @@
+#[test]
+fn invalid_attributes_do_not_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,19 @@
+use http_api_problem::IntoApiError;
+
+#[derive(IntoApiError)]
+#[api_error(status = 404, detail = "nope")]
+struct UnknownAttribute;
+
+#[derive(IntoApiError)]
+#[api_error(status = 404)]
+struct UnknownFieldAttribute {
+    #[api_error(skip)]
+    id: u32,
+}
+
+#[derive(IntoApiError)]
+union Union {
+    a: u32,
+}
+
+fn main() {}
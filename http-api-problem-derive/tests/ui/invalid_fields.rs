@@ -0,0 +1,16 @@
+use http_api_problem::IntoApiError;
+
+#[derive(IntoApiError)]
+#[api_error(status = 500)]
+struct TwoSources(#[source] std::io::Error, #[source] std::fmt::Error);
+
+#[derive(IntoApiError)]
+#[api_error(status = 500)]
+struct UnnamedField(#[api_error(field)] u32);
+
+#[derive(IntoApiError)]
+enum VariantWithoutAttribute {
+    Both(u32, u32),
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use http_api_problem::IntoApiError;
+
+#[derive(IntoApiError)]
+#[api_error(message = "{a.b}")]
+struct NotAField {
+    a: u32,
+}
+
+#[derive(IntoApiError)]
+#[api_error(message = "{type}")]
+struct Keyword {
+    a: u32,
+}
+
+#[derive(IntoApiError)]
+#[api_error(message = "{} {:?}")]
+struct EmptyPlaceholder(u32);
+
+#[derive(IntoApiError)]
+#[api_error(message = "{0")]
+struct Unterminated(u32);
+
+#[derive(IntoApiError)]
+#[api_error(message = "{0:width$}")]
+struct InvalidFormatSpec(u32);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use http_api_problem::IntoApiError;
+
+#[derive(IntoApiError)]
+#[api_error(status = 42)]
+struct OutOfRange;
+
+#[derive(IntoApiError)]
+#[api_error(status = "404")]
+struct NotAStatus;
+
+fn main() {}
@@ -0,0 +1,182 @@
+//! Problems for the errors of actix-web's extractors
+//!
+//! The errors of `Json`, `Query`, `Path` and `Form` can be converted
+//! into an [HttpApiProblem] (and an `ApiError` with the feature `api-error`).
+//! The status of the error is kept and its text becomes the `detail` if
+//! the status is a `4xx` status.
+//! A field named by the error (e.g. "missing field `name`") is added as
+//! an [InvalidParam].
+//!
+//! The error handlers of this module can be set on the configs of these
+//! extractors to respond with such a problem:
+//!
+//! ```rust
+//! # use actix_web_crate as actix_web;
+//! use actix_web::{web, App};
+//! use http_api_problem::*;
+//!
+//! let app = App::new()
+//!     .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+//!     .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+//!     .app_data(web::PathConfig::default().error_handler(path_error_handler))
+//!     .app_data(web::FormConfig::default().error_handler(form_error_handler))
+//!     .wrap(problem_error_handlers());
+//! ```
+use actix_web::body::MessageBody;
+use actix_web::dev::ServiceResponse;
+use actix_web::error::{
+    InternalError, JsonPayloadError, PathError, QueryPayloadError, UrlencodedError,
+};
+use actix_web::http::header;
+use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use actix_web::{HttpRequest, ResponseError};
+
+use super::*;
+
+/// An error handler for `JsonConfig` which responds with a problem
+///
+/// Requires the `actix-web` feature
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    problem_error(err)
+}
+
+/// An error handler for `QueryConfig` which responds with a problem
+///
+/// Requires the `actix-web` feature
+pub fn query_error_handler(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    problem_error(err)
+}
+
+/// An error handler for `PathConfig` which responds with a problem
+///
+/// Requires the `actix-web` feature
+pub fn path_error_handler(err: PathError, _req: &HttpRequest) -> actix_web::Error {
+    problem_error(err)
+}
+
+/// An error handler for `FormConfig` which responds with a problem
+///
+/// Requires the `actix-web` feature
+pub fn form_error_handler(err: UrlencodedError, _req: &HttpRequest) -> actix_web::Error {
+    problem_error(err)
+}
+
+/// Creates an `actix_web::Error` responding with the problem of `err`
+/// and keeping `err` as its cause
+fn problem_error<E: ResponseError + Error + 'static>(err: E) -> actix_web::Error {
+    let response = error_problem(&err)
+        .into_http_api_problem()
+        .to_actix_response();
+    InternalError::from_response(err, response).into()
+}
+
+/// Creates an [ErrorHandlers] middleware which replaces the body
+/// of every error response that is not already a problem with an
+/// [HttpApiProblem] for its status.
+///
+/// The text of the error which caused a `4xx` response becomes the `detail`.
+/// Errors causing a `5xx` response are not exposed.
+///
+/// Requires the `actix-web` feature
+pub fn problem_error_handlers<B: MessageBody + 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new().default_handler(problem_error_response)
+}
+
+fn problem_error_response<B>(
+    res: ServiceResponse<B>,
+) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let is_problem = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| {
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("application/problem+")
+        })
+        .unwrap_or(false);
+
+    if is_problem {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status =
+        StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut problem = HttpApiProblem::with_title_and_type(status);
+    if status.is_client_error() {
+        problem.detail = res.response().error().map(|err| err.to_string());
+    }
+
+    let (req, res) = res.into_parts();
+    let mut response = problem.to_actix_response();
+    for (name, value) in res.headers() {
+        if name != header::CONTENT_TYPE
+            && name != header::CONTENT_LENGTH
+            && name != header::CONTENT_ENCODING
+        {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+
+    Ok(ErrorHandlerResponse::Response(
+        ServiceResponse::new(req, response).map_into_right_body(),
+    ))
+}
+
+fn error_problem<E: ResponseError + Error>(err: &E) -> ValidationProblem {
+    let status = StatusCode::from_u16(err.status_code().as_u16())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut problem = ValidationProblem::new().status(status);
+    if status.is_client_error() {
+        problem = problem.detail(err.to_string());
+    }
+
+    let param = match err.source() {
+        Some(source) => InvalidParam::from_serde_message(&source.to_string()),
+        None => None,
+    };
+
+    match param {
+        Some(param) => problem.param(param),
+        None => problem,
+    }
+}
+
+macro_rules! impl_error_conversions {
+    ($($error:ty),*) => {
+        $(
+            impl From<&$error> for ValidationProblem {
+                fn from(err: &$error) -> Self {
+                    error_problem(err)
+                }
+            }
+
+            impl From<$error> for ValidationProblem {
+                fn from(err: $error) -> Self {
+                    error_problem(&err)
+                }
+            }
+
+            impl From<$error> for HttpApiProblem {
+                fn from(err: $error) -> Self {
+                    error_problem(&err).into_http_api_problem()
+                }
+            }
+
+            #[cfg(feature = "api-error")]
+            impl IntoApiError for $error {
+                fn into_api_error(self) -> ApiError {
+                    error_problem(&self).into_api_error()
+                }
+            }
+        )*
+    };
+}
+
+impl_error_conversions!(
+    JsonPayloadError,
+    QueryPayloadError,
+    PathError,
+    UrlencodedError
+);
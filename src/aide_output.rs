@@ -0,0 +1,86 @@
+//! [OperationOutput] for [HttpApiProblem] and `ApiError`
+//!
+//! Both are documented as the `default` response of an operation with
+//! an `application/problem+json` body described by the schema of the
+//! `json-schema` feature. A handler returning `Result<T, ApiError>`
+//! documents the response of `T` and the problem.
+//!
+//! #Example
+//!
+//! ```rust
+//! use aide::gen::in_context;
+//! use aide::openapi::Operation;
+//! use aide::OperationOutput;
+//! use http_api_problem::*;
+//!
+//! // The responses documented for a handler returning `Result<String, HttpApiProblem>`
+//! let responses = in_context(|ctx| {
+//!     <Result<String, HttpApiProblem>>::inferred_responses(ctx, &mut Operation::default())
+//! });
+//!
+//! // The response of `String` and the problem as the `default` response
+//! assert_eq!(2, responses.len());
+//! assert!(responses.iter().any(|(status, _)| status.is_none()));
+//! ```
+use aide::gen::GenContext;
+use aide::openapi::{MediaType, Operation, Response, SchemaObject};
+use aide::OperationOutput;
+use schemars::JsonSchema;
+
+use super::*;
+
+impl<Ext: JsonSchema> OperationOutput for HttpApiProblem<Ext> {
+    type Inner = Self;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(problem_response::<Ext>(ctx))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        _operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        vec![(None, problem_response::<Ext>(ctx))]
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl OperationOutput for ApiError {
+    type Inner = Self;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(problem_response::<()>(ctx))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        _operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        vec![(None, problem_response::<()>(ctx))]
+    }
+}
+
+fn problem_response<Ext: JsonSchema>(ctx: &mut GenContext) -> Response {
+    let schema = ctx
+        .schema
+        .subschema_for::<HttpApiProblem<Ext>>()
+        .into_object();
+
+    Response {
+        description: schema_descriptions::RESPONSE.to_string(),
+        content: [(
+            PROBLEM_JSON_MEDIA_TYPE.to_string(),
+            MediaType {
+                schema: Some(SchemaObject {
+                    json_schema: schema.into(),
+                    example: None,
+                    external_docs: None,
+                }),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    }
+}
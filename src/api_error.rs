@@ -7,6 +7,74 @@
 //!
 //! [ApiError] can be converted to an [HttpApiProblem] and
 //! also has many conversions to responses of web framewors implemented.
+//!
+//! # Deriving IntoApiError
+//!
+//! [IntoApiError] can be derived for structs and enums. The `ApiError`
+//! is described with an `#[api_error(...)]` attribute which accepts
+//! `status`, `title`, `message`, `type_url` and `instance`. All but `status`
+//! are format strings which can refer to the fields by name or index.
+//!
+//! A field annotated with `#[source]` becomes the source of the [ApiError]
+//! and fields annotated with `#[api_error(field)]` become fields of the [ApiError].
+//! Enum variants without an `#[api_error(...)]` attribute must have exactly one
+//! field which implements [IntoApiError] itself.
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! #[derive(IntoApiError)]
+//! #[api_error(status = 409, title = "Conflict", message = "account {account} is locked")]
+//! struct Locked {
+//!     #[api_error(field)]
+//!     account: u32,
+//! }
+//!
+//! #[derive(IntoApiError)]
+//! enum AccountError {
+//!     #[api_error(status = 404, type_url = "/probs/not-found", message = "account {0} not found")]
+//!     NotFound(u32),
+//!     #[api_error(status = StatusCode::SERVICE_UNAVAILABLE, title = "Storage unavailable")]
+//!     Storage(#[source] std::io::Error),
+//!     Locked(Locked),
+//! }
+//!
+//! let error: ApiError = AccountError::NotFound(12345).into();
+//! assert_eq!(StatusCode::NOT_FOUND, error.status());
+//! assert_eq!(Some("/probs/not-found"), error.type_url());
+//! assert_eq!(Some("account 12345 not found"), error.message());
+//!
+//! let io_error = std::io::Error::new(std::io::ErrorKind::Other, "disk on fire");
+//! let error: ApiError = AccountError::Storage(io_error).into();
+//! assert_eq!(StatusCode::SERVICE_UNAVAILABLE, error.status());
+//! assert_eq!(Some("disk on fire"), error.detail_message().as_deref());
+//!
+//! let error: ApiError = AccountError::Locked(Locked { account: 7 }).into();
+//! assert_eq!(StatusCode::CONFLICT, error.status());
+//! assert_eq!(Some("account 7 is locked"), error.message());
+//! assert_eq!(Some(&serde_json::json!(7)), error.fields().get("account"));
+//! ```
+//!
+//! Placeholders have to refer to a field and unknown attributes are
+//! rejected at compile time:
+//!
+//! ```compile_fail
+//! use http_api_problem::*;
+//!
+//! #[derive(IntoApiError)]
+//! #[api_error(status = 400, message = "{request.id} is invalid")]
+//! struct Invalid {
+//!     request: String,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! use http_api_problem::*;
+//!
+//! #[derive(IntoApiError)]
+//! #[api_error(status = 400, detail = "invalid")]
+//! struct Invalid;
+//! ```
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{self, Display};
@@ -294,8 +362,8 @@ impl ApiError {
 
     /// Set a URL that points to a detailed description of the error.
     ///
-    /// If not set it will most probably become `httpstatus.es.com/XXX` when
-    /// the problem response is generated.
+    /// If not set it will be derived from the status code as configured
+    /// with [StatusTypeUrl::set_default] when the problem response is generated.
     pub fn set_type_url<T: Display>(&mut self, type_url: T) {
         self.type_url = Some(type_url.to_string())
     }
@@ -389,65 +457,97 @@ impl ApiError {
 
     /// Creates an [HttpApiProblem] from this.
     ///
+    /// If no `type_url` is set, it is derived from the status code
+    /// as configured with [StatusTypeUrl::set_default].
+    ///
     /// Note: If the status is [StatusCode]::UNAUTHORIZED fields will
     /// **not** be put into the problem.
     pub fn to_http_api_problem(&self) -> HttpApiProblem {
-        let mut problem = HttpApiProblem::with_title_and_type(self.status);
-
-        problem.title.clone_from(&self.title);
-
-        if let Some(message) = self.detail_message() {
-            problem.detail = Some(message.into())
-        }
-
-        problem.type_url.clone_from(&self.type_url);
-        problem.instance.clone_from(&self.instance);
-
-        if self.status != StatusCode::UNAUTHORIZED {
-            for (key, value) in self.fields.iter() {
-                problem.set_value(key.to_string(), value);
-            }
-        }
+        self.to_http_api_problem_with(&StatusTypeUrl::get_default())
+    }
 
-        problem
+    /// Creates an [HttpApiProblem] from this.
+    ///
+    /// If no `type_url` is set, it is derived from the status code
+    /// as defined by the given [StatusTypeUrl].
+    ///
+    /// Note: If the status is [StatusCode]::UNAUTHORIZED fields will
+    /// **not** be put into the problem.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let error = ApiError::new(StatusCode::NOT_FOUND);
+    /// let problem = error.to_http_api_problem_with(&StatusTypeUrl::Mdn);
+    ///
+    /// assert_eq!(
+    ///     Some("https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/404"),
+    ///     problem.type_url.as_deref()
+    /// );
+    /// assert_eq!(Some("Not Found"), problem.title.as_deref());
+    /// ```
+    pub fn to_http_api_problem_with(&self, status_type_url: &StatusTypeUrl) -> HttpApiProblem {
+        problem_from_parts(
+            self.status,
+            self.title.clone(),
+            self.detail_message().map(Cow::into_owned),
+            self.type_url.clone(),
+            self.instance.clone(),
+            self.fields
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+            status_type_url,
+        )
     }
 
     /// Turns this into an [HttpApiProblem].
     ///
+    /// If no `type_url` is set, it is derived from the status code
+    /// as configured with [StatusTypeUrl::set_default].
+    ///
     /// Note: If the status is [StatusCode]::UNAUTHORIZED fields will
     /// **not** be put into the problem.
     pub fn into_http_api_problem(self) -> HttpApiProblem {
-        let mut problem = HttpApiProblem::with_title_and_type(self.status);
-
-        if let Some(title) = self.title.as_ref() {
-            problem.title = Some(title.to_owned());
-        }
-
-        if let Some(message) = self.detail_message() {
-            problem.detail = Some(message.into())
-        }
-
-        if let Some(type_url) = self.type_url.as_ref() {
-            problem.type_url = Some(type_url.to_owned())
-        }
-
-        if let Some(instance) = self.instance.as_ref() {
-            problem.instance = Some(instance.to_owned())
-        }
-
-        if self.status != StatusCode::UNAUTHORIZED {
-            for (key, value) in self.fields.iter() {
-                problem.set_value(key.to_string(), value);
-            }
-        }
+        self.into_http_api_problem_with(&StatusTypeUrl::get_default())
+    }
 
-        problem
+    /// Turns this into an [HttpApiProblem].
+    ///
+    /// If no `type_url` is set, it is derived from the status code
+    /// as defined by the given [StatusTypeUrl].
+    ///
+    /// Note: If the status is [StatusCode]::UNAUTHORIZED fields will
+    /// **not** be put into the problem.
+    pub fn into_http_api_problem_with(self, status_type_url: &StatusTypeUrl) -> HttpApiProblem {
+        let ApiError {
+            status,
+            title,
+            message,
+            instance,
+            type_url,
+            fields,
+            source,
+            ..
+        } = self;
+        let detail = message.or_else(|| source.map(|source| source.to_string()));
+
+        problem_from_parts(
+            status,
+            title,
+            detail,
+            type_url,
+            instance,
+            fields,
+            status_type_url,
+        )
     }
 
     /// If there is a message it will be the message otherwise the source error stringified
     ///
     /// If none is present, `None` is returned
-    pub fn detail_message(&self) -> Option<Cow<str>> {
+    pub fn detail_message(&self) -> Option<Cow<'_, str>> {
         if let Some(message) = self.message.as_ref() {
             return Some(Cow::Borrowed(message));
         }
@@ -486,6 +586,42 @@ impl ApiError {
         problem.into()
     }
 
+    /// Creates an `ntex` response containing a problem JSON.
+    ///
+    /// Requires the `ntex` feature
+    #[cfg(feature = "ntex")]
+    pub fn into_ntex_response(self) -> ntex::web::HttpResponse {
+        let problem = self.into_http_api_problem();
+        problem.to_ntex_response()
+    }
+
+    /// Creates a [warp] response containing a problem JSON.
+    ///
+    /// Requires the `warp` feature
+    #[cfg(feature = "warp")]
+    pub fn into_warp_response(self) -> warp::reply::Response {
+        let problem = self.into_http_api_problem();
+        problem.to_warp_response()
+    }
+
+    /// Creates a `rocket` response containing a problem JSON.
+    ///
+    /// Requires the `rocket` feature
+    #[cfg(feature = "rocket")]
+    pub fn into_rocket_response(self) -> rocket::Response<'static> {
+        let problem = self.into_http_api_problem();
+        problem.to_rocket_response()
+    }
+
+    /// Creates a [poem] response containing a problem JSON.
+    ///
+    /// Requires the `poem` feature
+    #[cfg(feature = "poem")]
+    pub fn into_poem_response(self) -> poem::Response {
+        let problem = self.into_http_api_problem();
+        problem.to_poem_response()
+    }
+
     /// Creates a [salvo] response containing a problem JSON.
     ///
     /// Requires the `salvo` feature
@@ -505,6 +641,49 @@ impl ApiError {
     }
 }
 
+/// Creates the [HttpApiProblem] of an [ApiError] from its parts.
+///
+/// The fields are only consumed if the status is not
+/// [StatusCode]::UNAUTHORIZED.
+fn problem_from_parts<I>(
+    status: StatusCode,
+    title: Option<String>,
+    detail: Option<String>,
+    type_url: Option<String>,
+    instance: Option<String>,
+    fields: I,
+    status_type_url: &StatusTypeUrl,
+) -> HttpApiProblem
+where
+    I: IntoIterator<Item = (String, Value)>,
+{
+    let mut problem = HttpApiProblem::with_title_and_type_from(status, status_type_url);
+
+    if title.is_some() {
+        problem.title = title;
+    }
+
+    if detail.is_some() {
+        problem.detail = detail;
+    }
+
+    if type_url.is_some() {
+        problem.type_url = type_url;
+    }
+
+    if instance.is_some() {
+        problem.instance = instance;
+    }
+
+    if status != StatusCode::UNAUTHORIZED {
+        for (key, value) in fields {
+            let _ = problem.try_insert_value(key, value);
+        }
+    }
+
+    problem
+}
+
 impl Error for ApiError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         self.source.as_ref().map(|e| &**e as _)
@@ -607,6 +786,62 @@ impl axum_core::response::IntoResponse for ApiError {
     }
 }
 
+/// A wrapped [ApiError] or [HttpApiProblem] is kept. Otherwise the text
+/// of the error becomes the message if the status is a `4xx` status.
+#[cfg(feature = "poem")]
+impl From<poem::Error> for ApiError {
+    fn from(error: poem::Error) -> Self {
+        let error = match error.downcast::<ApiError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+
+        if let Some(problem) = error.downcast_ref::<HttpApiProblem>() {
+            let mut builder =
+                ApiError::builder(problem.status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR));
+            builder.title = problem.title.clone();
+            builder.message = problem.detail.clone();
+            builder.type_url = problem.type_url.clone();
+            builder.instance = problem.instance.clone();
+            builder.fields = problem.additional_fields().clone();
+            return builder.finish();
+        }
+
+        let status = error.status();
+        let builder = ApiError::builder(status);
+        if status.is_client_error() {
+            builder.message(error).finish()
+        } else {
+            builder.finish()
+        }
+    }
+}
+
+#[cfg(feature = "poem")]
+impl From<ApiError> for poem::Response {
+    fn from(error: ApiError) -> poem::Response {
+        error.into_poem_response()
+    }
+}
+
+#[cfg(feature = "poem")]
+impl poem::IntoResponse for ApiError {
+    fn into_response(self) -> poem::Response {
+        self.into_poem_response()
+    }
+}
+
+#[cfg(feature = "poem")]
+impl poem::error::ResponseError for ApiError {
+    fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    fn as_response(&self) -> poem::Response {
+        self.to_http_api_problem().to_poem_response()
+    }
+}
+
 #[cfg(feature = "actix-web")]
 impl From<actix::prelude::MailboxError> for ApiError {
     fn from(error: actix::prelude::MailboxError) -> Self {
@@ -639,9 +874,41 @@ impl actix_web::error::ResponseError for ApiError {
     }
 }
 
+#[cfg(feature = "ntex")]
+impl From<ApiError> for ntex::web::HttpResponse {
+    fn from(error: ApiError) -> Self {
+        error.into_ntex_response()
+    }
+}
+
+#[cfg(feature = "ntex")]
+impl ntex::web::WebResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self, _: &ntex::web::HttpRequest) -> ntex::web::HttpResponse {
+        self.to_http_api_problem().to_ntex_response()
+    }
+}
+
 #[cfg(feature = "warp")]
 impl warp::reject::Reject for ApiError {}
 
+#[cfg(feature = "warp")]
+impl warp::Reply for ApiError {
+    fn into_response(self) -> warp::reply::Response {
+        self.into_warp_response()
+    }
+}
+
+#[cfg(feature = "rocket")]
+impl<'r> rocket::response::Responder<'r, 'static> for ApiError {
+    fn respond_to(self, _request: &rocket::Request) -> rocket::response::Result<'static> {
+        Ok(self.into_rocket_response())
+    }
+}
+
 #[cfg(feature = "salvo")]
 impl From<salvo::Error> for ApiError {
     fn from(error: salvo::Error) -> Self {
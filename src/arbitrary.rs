@@ -0,0 +1,281 @@
+//! Arbitrary problems for property based testing
+//!
+//! With the feature `proptest` [HttpApiProblem] and `ApiError` implement
+//! `proptest::arbitrary::Arbitrary` and with the feature `quickcheck`
+//! [HttpApiProblem] implements `quickcheck::Arbitrary`. `ApiError` does not
+//! implement `quickcheck::Arbitrary` since it is not `Clone`.
+//!
+//! The generated problems are valid:
+//!
+//! * the status is a client or server error with a canonical reason phrase
+//! * `type` is an absolute or relative URI or `about:blank`
+//! * `instance` is an absolute path
+//! * the extension members are nested JSON values with names which are
+//!   valid according to
+//!   [RFC9457, Section 3.2](https://www.rfc-editor.org/rfc/rfc9457#section-3.2)
+//!   and not reserved. Numbers are integers so that they survive a JSON
+//!   round trip unchanged.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "proptest")]
+//! # {
+//! use http_api_problem::*;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn json_round_trip(problem: HttpApiProblem) {
+//!         let parsed: HttpApiProblem = serde_json::from_str(&problem.json_string()).unwrap();
+//!         prop_assert_eq!(problem, parsed);
+//!     }
+//! }
+//!
+//! json_round_trip();
+//! # }
+//! ```
+use super::compliance::is_standard_member;
+use super::*;
+
+const MAX_JSON_DEPTH: u32 = 3;
+
+/// All client and server errors with a canonical reason phrase
+fn status_codes() -> Vec<StatusCode> {
+    (400..=599)
+        .filter_map(|code| StatusCode::from_u16(code).ok())
+        .filter(|status| status.canonical_reason().is_some())
+        .collect()
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_impls {
+    use proptest::prelude::*;
+    use proptest::{collection, option};
+
+    use super::*;
+
+    fn status_code() -> impl Strategy<Value = StatusCode> {
+        proptest::sample::select(status_codes())
+    }
+
+    fn type_url() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just(ABOUT_BLANK.to_string()),
+            "[a-z][a-z0-9-]{0,15}".prop_map(|slug| format!("https://example.com/probs/{}", slug)),
+            "[a-z][a-z0-9-]{0,15}".prop_map(|slug| format!("/probs/{}", slug)),
+        ]
+    }
+
+    fn instance() -> impl Strategy<Value = String> {
+        collection::vec("[a-z0-9][a-z0-9-]{0,7}", 1..4)
+            .prop_map(|segments| format!("/{}", segments.join("/")))
+    }
+
+    fn key() -> impl Strategy<Value = String> {
+        "[a-z][a-z0-9_]{2,15}".prop_filter("reserved key", |key| !is_standard_member(key))
+    }
+
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            any::<String>().prop_map(Value::from),
+        ];
+
+        leaf.prop_recursive(MAX_JSON_DEPTH, 32, 4, |inner| {
+            prop_oneof![
+                collection::vec(inner.clone(), 0..4).prop_map(Value::from),
+                collection::hash_map("[a-z][a-z0-9_-]{0,7}", inner, 0..4)
+                    .prop_map(|members| Value::Object(members.into_iter().collect())),
+            ]
+        })
+    }
+
+    fn extension_members() -> impl Strategy<Value = HashMap<String, Value>> {
+        collection::hash_map(key(), json_value(), 0..4)
+    }
+
+    impl Arbitrary for HttpApiProblem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (
+                option::of(status_code()),
+                option::of(type_url()),
+                option::of(any::<String>()),
+                option::of(any::<String>()),
+                option::of(instance()),
+                extension_members(),
+            )
+                .prop_map(
+                    |(status, type_url, title, detail, instance, additional_fields)| {
+                        HttpApiProblem {
+                            type_url,
+                            status,
+                            title,
+                            detail,
+                            instance,
+                            extension_members: (),
+                            additional_fields,
+                        }
+                    },
+                )
+                .boxed()
+        }
+    }
+
+    #[cfg(feature = "api-error")]
+    impl Arbitrary for ApiError {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (
+                status_code(),
+                option::of(type_url()),
+                option::of(any::<String>()),
+                option::of(any::<String>()),
+                option::of(instance()),
+                extension_members(),
+            )
+                .prop_map(|(status, type_url, title, message, instance, fields)| {
+                    let mut builder = ApiError::builder(status);
+                    builder.type_url = type_url;
+                    builder.title = title;
+                    builder.message = message;
+                    builder.instance = instance;
+                    builder.fields = fields;
+                    builder.finish()
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls {
+    use quickcheck::{Arbitrary, Gen};
+
+    use super::*;
+
+    const LETTERS: &[char] = &[
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+        's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    ];
+
+    /// The characters of path segments and of the keys of nested objects
+    const SLUG_CHARS: &[char] = &[
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+        's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+        '-', '_',
+    ];
+
+    /// The characters of extension member names
+    const NAME_CHARS: &[char] = &[
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+        's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+        '_',
+    ];
+
+    /// A number in `0..upper` depending on the size of the [Gen]
+    fn len(g: &mut Gen, upper: usize) -> usize {
+        usize::arbitrary(g) % upper.min(g.size().max(1))
+    }
+
+    fn slug(g: &mut Gen) -> String {
+        let first = *g.choose(LETTERS).expect("not empty");
+        let rest = (0..len(g, 16)).map(|_| *g.choose(SLUG_CHARS).expect("not empty"));
+        std::iter::once(first).chain(rest).collect()
+    }
+
+    fn type_url(g: &mut Gen) -> String {
+        match u8::arbitrary(g) % 3 {
+            0 => ABOUT_BLANK.to_string(),
+            1 => format!("https://example.com/probs/{}", slug(g)),
+            _ => format!("/probs/{}", slug(g)),
+        }
+    }
+
+    fn instance(g: &mut Gen) -> String {
+        let segments: Vec<String> = (0..=len(g, 3)).map(|_| slug(g)).collect();
+        format!("/{}", segments.join("/"))
+    }
+
+    fn key(g: &mut Gen) -> String {
+        loop {
+            let first = *g.choose(LETTERS).expect("not empty");
+            let rest = (0..2 + len(g, 14)).map(|_| *g.choose(NAME_CHARS).expect("not empty"));
+            let key: String = std::iter::once(first).chain(rest).collect();
+            if !is_standard_member(&key) {
+                return key;
+            }
+        }
+    }
+
+    fn json_value(g: &mut Gen, depth: u32) -> Value {
+        let kinds = if depth < MAX_JSON_DEPTH { 6 } else { 4 };
+        match u8::arbitrary(g) % kinds {
+            0 => Value::Null,
+            1 => Value::from(bool::arbitrary(g)),
+            2 => Value::from(i64::arbitrary(g)),
+            3 => Value::from(String::arbitrary(g)),
+            4 => Value::Array((0..len(g, 4)).map(|_| json_value(g, depth + 1)).collect()),
+            _ => Value::Object(
+                (0..len(g, 4))
+                    .map(|_| (slug(g), json_value(g, depth + 1)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn option<T>(g: &mut Gen, f: impl FnOnce(&mut Gen) -> T) -> Option<T> {
+        if bool::arbitrary(g) {
+            Some(f(g))
+        } else {
+            None
+        }
+    }
+
+    impl Arbitrary for HttpApiProblem {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let status_codes = status_codes();
+
+            HttpApiProblem {
+                type_url: option(g, type_url),
+                status: option(g, |g| *g.choose(&status_codes).expect("not empty")),
+                title: option(g, String::arbitrary),
+                detail: option(g, String::arbitrary),
+                instance: option(g, instance),
+                extension_members: (),
+                additional_fields: (0..len(g, 4)).map(|_| (key(g), json_value(g, 0))).collect(),
+            }
+        }
+
+        /// Removes the members one at a time
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            let mut shrunk = Vec::new();
+
+            let mut without = |f: fn(&mut HttpApiProblem) -> bool| {
+                let mut problem = self.clone();
+                if f(&mut problem) {
+                    shrunk.push(problem);
+                }
+            };
+            without(|p| p.type_url.take().is_some());
+            without(|p| p.status.take().is_some());
+            without(|p| p.title.take().is_some());
+            without(|p| p.detail.take().is_some());
+            without(|p| p.instance.take().is_some());
+
+            for key in self.additional_fields.keys() {
+                let mut problem = self.clone();
+                problem.additional_fields.remove(key);
+                shrunk.push(problem);
+            }
+
+            Box::new(shrunk.into_iter())
+        }
+    }
+}
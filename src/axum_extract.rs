@@ -0,0 +1,263 @@
+//! Problems for the rejections of axum's extractors
+//!
+//! The rejections of `Json`, `Path`, `Query`, `Form` and `TypedHeader`
+//! can be converted into an [HttpApiProblem] (and an `ApiError` with the
+//! feature `api-error`). The status of the rejection is kept and its text
+//! becomes the `detail` if the status is a `4xx` status. If the location of the error is known it is added
+//! as an [InvalidParam]:
+//!
+//! * `Json`: A JSON Pointer to the member which could not be deserialized
+//! * `Path`: A JSON Pointer to the path parameter which could not be parsed,
+//!   e.g. `/id` for a struct or `/1` for the second element of a tuple
+//! * `Query` and `Form`: The name of a missing or unknown field
+//!   (axum does not report the name of a field with an invalid value)
+//! * `TypedHeader`: The name of the header
+//!
+//! [Problem] is an extractor which rejects with such a problem.
+use std::error::Error;
+use std::ops::{Deref, DerefMut};
+
+use axum_core::extract::{FromRequest, FromRequestParts, Request};
+use axum_crate::async_trait;
+use axum_crate::extract::path::ErrorKind;
+use axum_crate::extract::rejection::{FormRejection, JsonRejection, PathRejection, QueryRejection};
+use axum_extra::typed_header::TypedHeaderRejection;
+use http::request::Parts;
+use serde_path_to_error::Segment;
+
+use super::*;
+
+/// An extractor which wraps another extractor and rejects
+/// with an [HttpApiProblem]
+///
+/// Requires the `axum-extract` feature
+///
+/// #Example
+///
+/// ```rust
+/// # use axum_crate as axum;
+/// use axum::{routing::post, Json, Router};
+/// use http_api_problem::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// async fn create_user(Problem(Json(user)): Problem<Json<User>>) -> StatusCode {
+///     // An invalid body is answered with a problem
+///     println!("created {}", user.name);
+///     StatusCode::CREATED
+/// }
+///
+/// let app: Router = Router::new().route("/users", post(create_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Problem<E>(pub E);
+
+impl<E> Problem<E> {
+    /// Returns the wrapped extractor
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<E> Deref for Problem<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> DerefMut for Problem<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait]
+impl<S, E> FromRequest<S> for Problem<E>
+where
+    S: Send + Sync,
+    E: FromRequest<S>,
+    E::Rejection: Into<HttpApiProblem>,
+{
+    type Rejection = HttpApiProblem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        E::from_request(req, state)
+            .await
+            .map(Problem)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<S, E> FromRequestParts<S> for Problem<E>
+where
+    S: Send + Sync,
+    E: FromRequestParts<S>,
+    E::Rejection: Into<HttpApiProblem>,
+{
+    type Rejection = HttpApiProblem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        E::from_request_parts(parts, state)
+            .await
+            .map(Problem)
+            .map_err(Into::into)
+    }
+}
+
+fn rejection_problem(status: StatusCode, detail: String) -> ValidationProblem {
+    let problem = ValidationProblem::new().status(status);
+    if status.is_client_error() {
+        problem.detail(detail)
+    } else {
+        problem
+    }
+}
+
+impl From<&JsonRejection> for ValidationProblem {
+    fn from(rejection: &JsonRejection) -> Self {
+        let problem = rejection_problem(rejection.status(), rejection.body_text());
+
+        match rejection {
+            JsonRejection::JsonDataError(err) => {
+                match find_source::<serde_path_to_error::Error<serde_json::Error>>(err) {
+                    Some(err) => {
+                        let segments = err.path().iter().filter_map(|segment| match segment {
+                            Segment::Seq { index } => Some(index.to_string()),
+                            Segment::Map { key } => Some(key.clone()),
+                            Segment::Enum { .. } | Segment::Unknown => None,
+                        });
+                        problem.pointer(json_pointer(segments), err.inner().to_string())
+                    }
+                    None => problem,
+                }
+            }
+            _ => problem,
+        }
+    }
+}
+
+impl From<&PathRejection> for ValidationProblem {
+    fn from(rejection: &PathRejection) -> Self {
+        let problem = rejection_problem(rejection.status(), rejection.body_text());
+
+        match rejection {
+            PathRejection::FailedToDeserializePathParams(err) => match err.kind() {
+                ErrorKind::ParseErrorAtKey { key, .. }
+                | ErrorKind::InvalidUtf8InPathParam { key } => {
+                    problem.pointer(json_pointer([key]), err.kind().to_string())
+                }
+                ErrorKind::ParseErrorAtIndex { index, .. } => {
+                    problem.pointer(json_pointer([index.to_string()]), err.kind().to_string())
+                }
+                _ => problem,
+            },
+            _ => problem,
+        }
+    }
+}
+
+impl From<&QueryRejection> for ValidationProblem {
+    fn from(rejection: &QueryRejection) -> Self {
+        let problem = rejection_problem(rejection.status(), rejection.body_text());
+
+        match rejection {
+            QueryRejection::FailedToDeserializeQueryString(err) => {
+                with_field_from_message(problem, err)
+            }
+            _ => problem,
+        }
+    }
+}
+
+impl From<&FormRejection> for ValidationProblem {
+    fn from(rejection: &FormRejection) -> Self {
+        let problem = rejection_problem(rejection.status(), rejection.body_text());
+
+        match rejection {
+            FormRejection::FailedToDeserializeForm(err) => with_field_from_message(problem, err),
+            FormRejection::FailedToDeserializeFormBody(err) => {
+                with_field_from_message(problem, err)
+            }
+            _ => problem,
+        }
+    }
+}
+
+impl From<&TypedHeaderRejection> for ValidationProblem {
+    fn from(rejection: &TypedHeaderRejection) -> Self {
+        let reason = if rejection.is_missing() {
+            "missing"
+        } else {
+            "invalid"
+        };
+
+        rejection_problem(StatusCode::BAD_REQUEST, rejection.to_string())
+            .query(rejection.name().as_str(), reason)
+    }
+}
+
+macro_rules! impl_rejection_conversions {
+    ($($rejection:ty),*) => {
+        $(
+            impl From<$rejection> for ValidationProblem {
+                fn from(rejection: $rejection) -> Self {
+                    ValidationProblem::from(&rejection)
+                }
+            }
+
+            impl From<$rejection> for HttpApiProblem {
+                fn from(rejection: $rejection) -> Self {
+                    ValidationProblem::from(&rejection).into_http_api_problem()
+                }
+            }
+
+            #[cfg(feature = "api-error")]
+            impl IntoApiError for $rejection {
+                fn into_api_error(self) -> ApiError {
+                    ValidationProblem::from(&self).into_api_error()
+                }
+            }
+        )*
+    };
+}
+
+impl_rejection_conversions!(
+    JsonRejection,
+    PathRejection,
+    QueryRejection,
+    FormRejection,
+    TypedHeaderRejection
+);
+
+/// Finds an error of type `T` in the chain of sources of `err`
+fn find_source<'a, T: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a T> {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(found) = err.downcast_ref::<T>() {
+            return Some(found);
+        }
+        current = err.source();
+    }
+    None
+}
+
+/// Adds the field of a serde error like "missing field `name`"
+/// if the message contains one.
+fn with_field_from_message<E: Error>(problem: ValidationProblem, err: &E) -> ValidationProblem {
+    let message = match err.source() {
+        Some(source) => source.to_string(),
+        None => err.to_string(),
+    };
+
+    match InvalidParam::from_serde_message(&message) {
+        Some(param) => problem.param(param),
+        None => problem,
+    }
+}
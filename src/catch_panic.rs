@@ -0,0 +1,172 @@
+//! Middlewares which turn panics of handlers into problems.
+//!
+//! A panicking handler usually drops the connection or results in an
+//! empty response. The middlewares of this module catch the panic and
+//! respond with a `500 - Internal Server Error` problem instead.
+//!
+//! The `instance` of the problem is a generated occurrence id
+//! (`urn:uuid:...`) which can be used to find the panic in the logs.
+//! The panic message and location are not exposed to the client. They are
+//! kept as [PanicDetails] in the extensions of the [ApiError] which is passed
+//! to the `on_panic` callback of the middlewares and in the extensions of the response.
+//!
+//! [ApiError::from_panic] creates such an error for panics caught by other means.
+//!
+//! * [CatchPanicLayer] for [tower](https://docs.rs/tower) (features `catch-panic` and `tower`)
+//! * [CatchPanic] for [actix-web](https://docs.rs/actix-web) (features `catch-panic` and `actix-web`)
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic;
+use std::sync::Once;
+
+use super::*;
+
+#[cfg(feature = "actix-web")]
+mod actix;
+#[cfg(feature = "tower")]
+mod tower;
+#[cfg(any(feature = "tower", feature = "actix-web"))]
+mod unwind;
+
+#[cfg(any(feature = "tower", feature = "actix-web"))]
+use self::unwind::*;
+
+#[cfg(feature = "actix-web")]
+pub use self::actix::*;
+#[cfg(feature = "tower")]
+pub use self::tower::*;
+
+/// The details of a caught panic.
+///
+/// Available in the extensions of the [ApiError] passed to `on_panic`
+/// and in the extensions of the response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicDetails {
+    /// The message of the panic if the payload was a string
+    pub message: Option<String>,
+    /// The location of the panic as `file:line:column`
+    pub location: Option<String>,
+}
+
+impl PanicDetails {
+    /// Returns the details of an [ApiError] created for a caught panic
+    pub fn of(error: &ApiError) -> Option<&PanicDetails> {
+        error.extensions().get::<PanicDetails>()
+    }
+}
+
+/// The details of the last panic of a thread recorded by the hook
+struct RecordedPanic {
+    /// Identifies the payload of the panic, see [payload_key]
+    key: Option<(usize, usize)>,
+    details: PanicDetails,
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<RecordedPanic>> = const { RefCell::new(None) };
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Installs a panic hook which records the location of a panic
+/// before invoking the previously installed hook.
+///
+/// The hook is only installed once. The middlewares install it
+/// when they are created. Call it before using [ApiError::from_panic]
+/// to get the location of panics.
+pub fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let recorded = RecordedPanic {
+                key: payload_key(info.payload()),
+                details: PanicDetails {
+                    message: payload_message(info.payload()),
+                    location: info
+                        .location()
+                        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+                },
+            };
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(recorded));
+            previous(info);
+        }));
+    });
+}
+
+/// Takes the details recorded for the last panic of the current thread
+fn take_recorded_panic() -> Option<RecordedPanic> {
+    LAST_PANIC.with(|last| last.borrow_mut().take())
+}
+
+/// Identifies a payload with a message by the address and length of the
+/// message. The message is not moved when the payload is boxed after
+/// the hook ran, so this is the same for the hook and the caught payload.
+fn payload_key(payload: &(dyn Any + Send)) -> Option<(usize, usize)> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (s.as_ptr() as usize, s.len()))
+        .or_else(|| {
+            payload
+                .downcast_ref::<String>()
+                .map(|s| (s.as_ptr() as usize, s.len()))
+        })
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> Option<String> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+}
+
+impl ApiError {
+    /// Creates a `500 - Internal Server Error` for the payload of a caught panic.
+    ///
+    /// The `instance` is a generated occurrence id. The message and the
+    /// location of the panic are added as [PanicDetails] to the extensions.
+    /// The location is only known if the hook was installed via [install_panic_hook],
+    /// the panic has a message and it was the last panic of the current thread.
+    /// It is unknown for payloads which crossed threads, e.g. of a `JoinError`.
+    ///
+    /// Requires the `catch-panic` feature
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// install_panic_hook();
+    ///
+    /// let payload = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+    /// let error = ApiError::from_panic(payload);
+    ///
+    /// assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, error.status());
+    /// assert!(error.instance().unwrap().starts_with("urn:uuid:"));
+    ///
+    /// let details = PanicDetails::of(&error).unwrap();
+    /// assert_eq!(Some("boom"), details.message.as_deref());
+    /// assert!(details.location.is_some());
+    /// ```
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> ApiError {
+        let key = payload_key(&*payload);
+        let recorded = take_recorded_panic()
+            .filter(|recorded| key.is_some() && recorded.key == key)
+            .map(|recorded| recorded.details);
+
+        panic_api_error(payload, recorded)
+    }
+}
+
+/// Creates the error for a payload and the details recorded for it
+fn panic_api_error(payload: Box<dyn Any + Send>, recorded: Option<PanicDetails>) -> ApiError {
+    let details = PanicDetails {
+        message: payload_message(&*payload)
+            .or_else(|| recorded.as_ref().and_then(|r| r.message.clone())),
+        location: recorded.and_then(|r| r.location),
+    };
+
+    ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR)
+        .instance(format!("urn:uuid:{}", uuid::Uuid::new_v4()))
+        .extension(details)
+        .finish()
+}
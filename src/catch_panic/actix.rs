@@ -0,0 +1,130 @@
+//! Catching panics with [actix-web](https://docs.rs/actix-web)
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::InternalError;
+
+use super::*;
+
+/// A middleware which responds with a `500 - Internal Server Error`
+/// problem if a handler panics.
+///
+/// Requires the features `catch-panic` and `actix-web`
+///
+/// #Example
+///
+/// ```rust
+/// # use actix_web_crate as actix_web;
+/// use actix_web::{web, App};
+/// use http_api_problem::*;
+///
+/// async fn handler() -> &'static str {
+///     panic!("boom")
+/// }
+///
+/// let app = App::new()
+///     .wrap(CatchPanic::new().on_panic(|err| {
+///         let details = PanicDetails::of(err);
+///         eprintln!("{:?} panicked: {:?}", err.instance(), details);
+///     }))
+///     .route("/", web::get().to(handler));
+/// ```
+#[derive(Clone)]
+pub struct CatchPanic {
+    on_panic: Option<OnPanic>,
+}
+
+impl Default for CatchPanic {
+    /// Creates a new middleware and installs the panic hook which
+    /// records the location of panics
+    fn default() -> Self {
+        install_panic_hook();
+        Self { on_panic: None }
+    }
+}
+
+impl CatchPanic {
+    /// Creates a new middleware and installs the panic hook which
+    /// records the location of panics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback which is invoked with the [ApiError] of every
+    /// caught panic, e.g. to log it.
+    pub fn on_panic<F>(mut self, on_panic: F) -> Self
+    where
+        F: Fn(&ApiError) + Send + Sync + 'static,
+    {
+        self.on_panic = Some(Arc::new(on_panic));
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CatchPanic
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = CatchPanicMiddleware<S>;
+    type InitError = ();
+    type Future = std::future::Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        install_panic_hook();
+        std::future::ready(Ok(CatchPanicMiddleware {
+            service: Rc::new(service),
+            on_panic: self.on_panic.clone(),
+        }))
+    }
+}
+
+/// The service created by the [CatchPanic] middleware
+///
+/// Requires the features `catch-panic` and `actix-web`
+pub struct CatchPanicMiddleware<S> {
+    service: Rc<S>,
+    on_panic: Option<OnPanic>,
+}
+
+impl<S, B> Service<ServiceRequest> for CatchPanicMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let on_panic = self.on_panic.clone();
+
+        Box::pin(async move {
+            let result = match catch_unwind(|| service.call(req)) {
+                Ok(future) => CatchUnwind::new(future).await,
+                Err(panic) => Err(panic),
+            };
+
+            result.unwrap_or_else(|panic| {
+                let (problem, details) = handle_panic(panic, on_panic.as_ref());
+                let mut response = problem.to_actix_response();
+                if let Some(details) = details {
+                    response.extensions_mut().insert(details);
+                }
+
+                // The request was moved into the panicked service, so the
+                // problem is returned as an error rendered by actix-web.
+                Err(InternalError::from_response(problem, response).into())
+            })
+        })
+    }
+}
@@ -0,0 +1,181 @@
+//! Catching panics with [tower](https://docs.rs/tower)
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use ::tower_layer::Layer;
+use ::tower_service::Service;
+use bytes::Bytes;
+use http::header::{self, HeaderValue};
+use http::Response;
+use http_body::Body;
+use http_body_util::{Either, Full};
+use pin_project_lite::pin_project;
+
+use super::*;
+
+/// A [Layer] which responds with a `500 - Internal Server Error`
+/// problem if the inner service panics.
+///
+/// Requires the features `catch-panic` and `tower`
+///
+/// #Example
+///
+/// ```rust
+/// # #[cfg(feature = "axum-extract")]
+/// # {
+/// # use axum_crate as axum;
+/// use axum::{routing::get, Router};
+/// use http_api_problem::*;
+///
+/// async fn handler() -> &'static str {
+///     panic!("boom")
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/", get(handler))
+///     .layer(CatchPanicLayer::new().on_panic(|err| {
+///         let details = PanicDetails::of(err);
+///         eprintln!("{:?} panicked: {:?}", err.instance(), details);
+///     }));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CatchPanicLayer {
+    on_panic: Option<OnPanic>,
+}
+
+impl Default for CatchPanicLayer {
+    /// Creates a new layer and installs the panic hook which
+    /// records the location of panics
+    fn default() -> Self {
+        install_panic_hook();
+        Self { on_panic: None }
+    }
+}
+
+impl CatchPanicLayer {
+    /// Creates a new layer and installs the panic hook which
+    /// records the location of panics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback which is invoked with the [ApiError] of every
+    /// caught panic, e.g. to log it.
+    pub fn on_panic<F>(mut self, on_panic: F) -> Self
+    where
+        F: Fn(&ApiError) + Send + Sync + 'static,
+    {
+        self.on_panic = Some(Arc::new(on_panic));
+        self
+    }
+}
+
+impl<S> Layer<S> for CatchPanicLayer {
+    type Service = CatchPanicService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        install_panic_hook();
+        CatchPanicService {
+            inner,
+            on_panic: self.on_panic.clone(),
+        }
+    }
+}
+
+/// A [Service] which responds with a `500 - Internal Server Error`
+/// problem if the inner service panics.
+///
+/// Created by [CatchPanicLayer].
+///
+/// Requires the features `catch-panic` and `tower`
+#[derive(Clone)]
+pub struct CatchPanicService<S> {
+    inner: S,
+    on_panic: Option<OnPanic>,
+}
+
+impl<S, Req, B> Service<Req> for CatchPanicService<S>
+where
+    S: Service<Req, Response = Response<B>>,
+    B: Body<Data = Bytes>,
+{
+    type Response = Response<ProblemBody<B>>;
+    type Error = S::Error;
+    type Future = CatchPanicFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let state = match catch_unwind(|| self.inner.call(req)) {
+            Ok(future) => State::Future {
+                future: CatchUnwind::new(future),
+            },
+            Err(panic) => State::Panicked { panic: Some(panic) },
+        };
+
+        CatchPanicFuture {
+            state,
+            on_panic: self.on_panic.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of a [CatchPanicService]
+    pub struct CatchPanicFuture<F> {
+        #[pin]
+        state: State<F>,
+        on_panic: Option<OnPanic>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F> {
+        Future {
+            #[pin]
+            future: CatchUnwind<F>,
+        },
+        Panicked {
+            panic: Option<CaughtPanic>,
+        },
+    }
+}
+
+impl<F, B, E> Future for CatchPanicFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<ProblemBody<B>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let panic = match this.state.project() {
+            StateProj::Future { future } => match std::task::ready!(future.poll(cx)) {
+                Ok(result) => return Poll::Ready(result.map(|res| res.map(Either::Left))),
+                Err(panic) => panic,
+            },
+            StateProj::Panicked { panic } => panic.take().expect("polled after completion"),
+        };
+
+        let (problem, details) = handle_panic(panic, this.on_panic.as_ref());
+        let body = problem.json_bytes();
+
+        let mut response = Response::new(Either::Right(Full::new(Bytes::from(body))));
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_JSON_MEDIA_TYPE),
+        );
+        if let Some(details) = details {
+            response.extensions_mut().insert(details);
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
@@ -0,0 +1,74 @@
+//! Catching panics of futures for the middlewares
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+
+use super::*;
+
+/// A callback which is invoked with the [ApiError] of a caught panic
+pub(crate) type OnPanic = Arc<dyn Fn(&ApiError) + Send + Sync>;
+
+/// A caught panic with the details recorded while catching it
+pub(crate) struct CaughtPanic {
+    payload: Box<dyn Any + Send>,
+    recorded: Option<PanicDetails>,
+}
+
+/// Invokes `f` and catches a panic.
+///
+/// Details recorded before are discarded, so that the recorded
+/// details of a caught panic belong to this panic.
+pub(crate) fn catch_unwind<R>(f: impl FnOnce() -> R) -> Result<R, CaughtPanic> {
+    take_recorded_panic();
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| CaughtPanic {
+        payload,
+        recorded: take_recorded_panic().map(|recorded| recorded.details),
+    })
+}
+
+/// Handles a caught panic by invoking `on_panic` and
+/// creating the problem for the response
+pub(crate) fn handle_panic(
+    panic: CaughtPanic,
+    on_panic: Option<&OnPanic>,
+) -> (HttpApiProblem, Option<PanicDetails>) {
+    let error = panic_api_error(panic.payload, panic.recorded);
+    if let Some(on_panic) = on_panic {
+        on_panic(&error);
+    }
+    let details = PanicDetails::of(&error).cloned();
+
+    (error.into_http_api_problem(), details)
+}
+
+pin_project! {
+    /// A future which catches a panic while being polled
+    pub(crate) struct CatchUnwind<F> {
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F> CatchUnwind<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self { future }
+    }
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, CaughtPanic>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.project().future;
+        match catch_unwind(|| future.poll(cx)) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Checking problems for compliance with RFC 9457
+//!
+//! An [HttpApiProblem] accepts whatever it is given, and so does its
+//! deserialization: an invalid status code becomes `None` and `type` and
+//! `instance` are arbitrary strings. [HttpApiProblem::validate] reports
+//! everything that does not comply with
+//! [RFC9457](https://www.rfc-editor.org/rfc/rfc9457) as a [ComplianceError]:
+//!
+//! * `type` or `instance` is not a URI reference
+//! * `status` is not a client or server error (`400` to `599`)
+//! * the name of an extension member is not valid
+//!   ([RFC9457, Section 3.2](https://www.rfc-editor.org/rfc/rfc9457#section-3.2))
+//!   or is the name of a standard member
+//!
+//! [HttpApiProblem::validate_response_status] additionally checks that the
+//! `status` equals the status of the HTTP response. The strict
+//! deserialization of [HttpApiProblem::from_json_str_strict] reports an
+//! invalid `status` instead of dropping it and validates the problem.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! let json = r#"{"type": "probs/out of credit", "status": 200, "balance": 30}"#;
+//!
+//! let err = HttpApiProblem::from_json_str_strict(json).unwrap_err();
+//!
+//! assert_eq!(
+//!     &[
+//!         ComplianceViolation::InvalidTypeUrl("probs/out of credit".to_string()),
+//!         ComplianceViolation::StatusOutOfRange(StatusCode::OK),
+//!     ],
+//!     err.violations()
+//! );
+//! ```
+use super::*;
+
+/// The names of the members defined by RFC 9457
+const STANDARD_MEMBERS: &[&str] = &["type", "status", "title", "detail", "instance"];
+
+/// A way in which a problem does not comply with RFC 9457
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplianceViolation {
+    /// The document is not a JSON object or a member has the wrong type
+    InvalidDocument(String),
+    /// The `type` is not a URI reference
+    InvalidTypeUrl(String),
+    /// The `instance` is not a URI reference
+    InvalidInstance(String),
+    /// The `status` is not an HTTP status code. Contains the JSON of the member.
+    InvalidStatus(String),
+    /// The `status` is not a client or server error
+    StatusOutOfRange(StatusCode),
+    /// The `status` differs from the status of the HTTP response
+    StatusMismatch {
+        /// The `status` of the problem
+        problem: StatusCode,
+        /// The status of the HTTP response
+        response: StatusCode,
+    },
+    /// The name of an extension member does not start with a letter, is
+    /// shorter than three characters or contains other characters than
+    /// letters, digits and `_`
+    InvalidExtensionName(String),
+    /// An extension member has the name of a member defined by RFC 9457
+    ReservedExtensionName(String),
+}
+
+impl fmt::Display for ComplianceViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComplianceViolation::InvalidDocument(message) => {
+                write!(f, "invalid problem document: {}", message)
+            }
+            ComplianceViolation::InvalidTypeUrl(type_url) => {
+                write!(f, "'type' is not a URI reference: {:?}", type_url)
+            }
+            ComplianceViolation::InvalidInstance(instance) => {
+                write!(f, "'instance' is not a URI reference: {:?}", instance)
+            }
+            ComplianceViolation::InvalidStatus(status) => {
+                write!(f, "'status' is not an HTTP status code: {}", status)
+            }
+            ComplianceViolation::StatusOutOfRange(status) => write!(
+                f,
+                "'status' {} is not a client or server error",
+                status.as_u16()
+            ),
+            ComplianceViolation::StatusMismatch { problem, response } => write!(
+                f,
+                "'status' {} differs from the response status {}",
+                problem.as_u16(),
+                response.as_u16()
+            ),
+            ComplianceViolation::InvalidExtensionName(name) => {
+                write!(f, "'{}' is not a valid extension member name", name)
+            }
+            ComplianceViolation::ReservedExtensionName(name) => {
+                write!(
+                    f,
+                    "'{}' is reserved and can not be an extension member",
+                    name
+                )
+            }
+        }
+    }
+}
+
+/// The violations of RFC 9457 found in a problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceError {
+    violations: Vec<ComplianceViolation>,
+}
+
+impl ComplianceError {
+    /// The violations found in the problem
+    pub fn violations(&self) -> &[ComplianceViolation] {
+        &self.violations
+    }
+
+    /// Returns the violations
+    pub fn into_violations(self) -> Vec<ComplianceViolation> {
+        self.violations
+    }
+
+    fn invalid_document<T: fmt::Display>(message: T) -> Self {
+        Self {
+            violations: vec![ComplianceViolation::InvalidDocument(message.to_string())],
+        }
+    }
+
+    fn result(violations: Vec<ComplianceViolation>) -> Result<(), Self> {
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Self { violations })
+        }
+    }
+}
+
+impl fmt::Display for ComplianceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the problem does not comply with RFC 9457: ")?;
+        for (idx, violation) in self.violations.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ComplianceError {}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Checks that the problem complies with RFC 9457.
+    ///
+    /// Missing members are fine. The member `invalid-params` is accepted
+    /// although its name contains a `-` since RFC 9457 uses it in its examples.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let mut p = HttpApiProblem::with_title(StatusCode::FORBIDDEN)
+    ///     .type_url("https://example.com/probs/out-of-credit")
+    ///     .instance("/account/12345/msgs/abc")
+    ///     .value("balance", &30);
+    ///
+    /// assert!(p.validate().is_ok());
+    ///
+    /// p.additional_fields_mut().insert("status".to_string(), 200.into());
+    ///
+    /// assert_eq!(
+    ///     &[ComplianceViolation::ReservedExtensionName("status".to_string())],
+    ///     p.validate().unwrap_err().violations()
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), ComplianceError> {
+        ComplianceError::result(self.violations())
+    }
+
+    /// Checks that the problem complies with RFC 9457 and that its
+    /// `status` (if any) equals the status of the HTTP response.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::NOT_FOUND);
+    ///
+    /// assert!(p.validate_response_status(StatusCode::NOT_FOUND).is_ok());
+    ///
+    /// assert_eq!(
+    ///     &[ComplianceViolation::StatusMismatch {
+    ///         problem: StatusCode::NOT_FOUND,
+    ///         response: StatusCode::BAD_REQUEST,
+    ///     }],
+    ///     p.validate_response_status(StatusCode::BAD_REQUEST)
+    ///         .unwrap_err()
+    ///         .violations()
+    /// );
+    /// ```
+    pub fn validate_response_status<T: Into<StatusCode>>(
+        &self,
+        response_status: T,
+    ) -> Result<(), ComplianceError> {
+        let response = response_status.into();
+        let mut violations = self.violations();
+        if let Some(problem) = self.status.filter(|status| *status != response) {
+            violations.push(ComplianceViolation::StatusMismatch { problem, response });
+        }
+        ComplianceError::result(violations)
+    }
+
+    fn violations(&self) -> Vec<ComplianceViolation> {
+        let mut violations = Vec::new();
+
+        if let Some(type_url) = self.type_url.as_ref() {
+            if !uri::is_uri_reference(type_url) {
+                violations.push(ComplianceViolation::InvalidTypeUrl(type_url.clone()));
+            }
+        }
+
+        if let Some(status) = self.status {
+            if !(status.is_client_error() || status.is_server_error()) {
+                violations.push(ComplianceViolation::StatusOutOfRange(status));
+            }
+        }
+
+        if let Some(instance) = self.instance.as_ref() {
+            if !uri::is_uri_reference(instance) {
+                violations.push(ComplianceViolation::InvalidInstance(instance.clone()));
+            }
+        }
+
+        for name in self.extension_member_names() {
+            if is_standard_member(&name) {
+                violations.push(ComplianceViolation::ReservedExtensionName(name));
+            } else if !is_extension_name(&name) {
+                violations.push(ComplianceViolation::InvalidExtensionName(name));
+            }
+        }
+
+        violations
+    }
+
+    /// The names of the typed and untyped extension members in order
+    fn extension_member_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.additional_fields.keys().cloned().collect();
+        if let Ok(Value::Object(typed)) = serde_json::to_value(&self.extension_members) {
+            names.extend(typed.into_iter().map(|(name, _)| name));
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+impl HttpApiProblem {
+    /// Parses a problem from JSON and checks that it complies with RFC 9457.
+    ///
+    /// Unlike deserializing with `serde` an invalid `status` is reported
+    /// as [ComplianceViolation::InvalidStatus] instead of being dropped.
+    /// Use [HttpApiProblem::validate_response_status] to compare the `status`
+    /// with the status of the response and [HttpApiProblem::into_typed]
+    /// for typed extension members.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::from_json_str_strict(r#"{"status": 403, "balance": 30}"#).unwrap();
+    /// assert_eq!(Some(StatusCode::FORBIDDEN), p.status);
+    ///
+    /// let err = HttpApiProblem::from_json_str_strict(r#"{"status": 1000}"#).unwrap_err();
+    /// assert_eq!(
+    ///     &[ComplianceViolation::InvalidStatus("1000".to_string())],
+    ///     err.violations()
+    /// );
+    /// ```
+    pub fn from_json_str_strict(json: &str) -> Result<Self, ComplianceError> {
+        Self::from_json_slice_strict(json.as_bytes())
+    }
+
+    /// Parses a problem from JSON and checks that it complies with RFC 9457.
+    ///
+    /// See [HttpApiProblem::from_json_str_strict].
+    pub fn from_json_slice_strict(json: &[u8]) -> Result<Self, ComplianceError> {
+        let mut document = match serde_json::from_slice::<Value>(json) {
+            Ok(Value::Object(document)) => document,
+            Ok(other) => {
+                return Err(ComplianceError::invalid_document(format!(
+                    "expected a JSON object but got {}",
+                    other
+                )))
+            }
+            Err(err) => return Err(ComplianceError::invalid_document(err)),
+        };
+
+        // Removed so that the remaining members are still checked
+        let invalid_status = match document.get("status") {
+            Some(status) if !status.is_null() && !is_status_code(status) => document
+                .remove("status")
+                .map(|status| ComplianceViolation::InvalidStatus(status.to_string())),
+            _ => None,
+        };
+
+        let problem: HttpApiProblem = serde_json::from_value(Value::Object(document))
+            .map_err(ComplianceError::invalid_document)?;
+
+        let mut violations: Vec<_> = invalid_status.into_iter().collect();
+        violations.extend(problem.violations());
+        ComplianceError::result(violations)?;
+
+        Ok(problem)
+    }
+}
+
+fn is_status_code(status: &Value) -> bool {
+    status
+        .as_u64()
+        .and_then(|code| u16::try_from(code).ok())
+        .is_some_and(|code| StatusCode::from_u16(code).is_ok())
+}
+
+/// An extension member name as recommended by
+/// [RFC9457, Section 3.2](https://www.rfc-editor.org/rfc/rfc9457#section-3.2)
+fn is_extension_name(name: &str) -> bool {
+    if name == INVALID_PARAMS_KEY {
+        return true;
+    }
+
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.len() >= 3
+}
+
+/// Returns `true` if the name is the name of a member defined by RFC 9457
+pub(crate) fn is_standard_member(name: &str) -> bool {
+    STANDARD_MEMBERS.contains(&name)
+}
+
+/// Removes the standard members from the untyped extension members
+pub(crate) fn without_standard_members(
+    fields: &HashMap<String, Value>,
+) -> impl Iterator<Item = (&String, &Value)> {
+    fields.iter().filter(|(name, _)| !is_standard_member(name))
+}
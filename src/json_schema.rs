@@ -0,0 +1,105 @@
+//! [JsonSchema] for [HttpApiProblem]
+//!
+//! This is implemented manually so that the schema of an untyped
+//! [HttpApiProblem] is named `HttpApiProblem` and the typed extension
+//! members get flattened into the schema.
+use std::borrow::Cow;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Metadata, Schema, SchemaObject};
+use schemars::JsonSchema;
+
+use super::*;
+
+impl<Ext: JsonSchema> JsonSchema for HttpApiProblem<Ext> {
+    fn schema_name() -> String {
+        if is_unit::<Ext>() {
+            schema_descriptions::SCHEMA_NAME.to_string()
+        } else {
+            format!("HttpApiProblem_for_{}", Ext::schema_name())
+        }
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        if is_unit::<Ext>() {
+            Cow::Borrowed(concat!(module_path!(), "::HttpApiProblem"))
+        } else {
+            Cow::Owned(format!(
+                "{}::HttpApiProblem_for_{}",
+                module_path!(),
+                Ext::schema_id()
+            ))
+        }
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            metadata: Some(Box::new(Metadata {
+                description: Some(schema_descriptions::PROBLEM.to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let properties = [
+            (
+                "detail",
+                gen.subschema_for::<Option<String>>(),
+                schema_descriptions::DETAIL,
+            ),
+            (
+                "instance",
+                gen.subschema_for::<Option<String>>(),
+                schema_descriptions::INSTANCE,
+            ),
+            (
+                "status",
+                gen.subschema_for::<u16>(),
+                schema_descriptions::STATUS,
+            ),
+            (
+                "title",
+                gen.subschema_for::<Option<String>>(),
+                schema_descriptions::TITLE,
+            ),
+            (
+                "type",
+                gen.subschema_for::<Option<String>>(),
+                schema_descriptions::TYPE,
+            ),
+        ];
+
+        let object = schema.object();
+        for (name, property, description) in properties {
+            object
+                .properties
+                .insert(name.to_string(), with_description(property, description));
+        }
+        object.additional_properties = Some(Box::new(Schema::Bool(true)));
+
+        if let Schema::Object(mut ext) = Ext::json_schema(gen) {
+            if let Some(ext_object) = ext.object.take() {
+                let object = schema.object();
+                object.properties.extend(ext_object.properties);
+                object.required.extend(ext_object.required);
+            }
+        }
+
+        Schema::Object(schema)
+    }
+}
+
+fn is_unit<T: JsonSchema>() -> bool {
+    T::schema_id() == <()>::schema_id()
+}
+
+fn with_description(schema: Schema, description: &str) -> Schema {
+    match schema {
+        Schema::Object(mut object) => {
+            object.metadata().description = Some(description.to_string());
+            Schema::Object(object)
+        }
+        other => other,
+    }
+}
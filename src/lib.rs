@@ -8,6 +8,7 @@
 //! [![license-apache](http://img.shields.io/badge/license-APACHE-blue.svg)](https://github.com/chridou/http-api-problem/blob/master/LICENSE-APACHE)
 //!
 //! A library to create HTTP response content for APIs based on
+//! [RFC9457](https://www.rfc-editor.org/rfc/rfc9457) which obsoletes
 //! [RFC7807](https://tools.ietf.org/html/rfc7807).
 //!
 //! ## Usage
@@ -71,6 +72,73 @@
 //! used as a fallback. This can be easily avoided by only using those constructor
 //! functions which require a [StatusCode].
 //!
+//! ## RFC 9457
+//!
+//! [RFC9457](https://www.rfc-editor.org/rfc/rfc9457) replaced
+//! [RFC7807](https://tools.ietf.org/html/rfc7807). Both describe the same
+//! document format but RFC 9457 states that a missing `type` member
+//! has to be treated as `about:blank` and that the `title` of an `about:blank`
+//! problem should be the reason phrase of the HTTP status code.
+//!
+//! To create problems following these rules use [HttpApiProblem::about_blank]
+//! or normalize an existing (e.g. received) problem with [HttpApiProblem::into_rfc9457]:
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! let p = HttpApiProblem::new(StatusCode::NOT_FOUND).into_rfc9457();
+//!
+//! assert_eq!(Some(ABOUT_BLANK), p.type_url.as_deref());
+//! assert_eq!(Some("Not Found"), p.title.as_deref());
+//! ```
+//!
+//! The `type` member may be a relative URI reference. It can be resolved
+//! against the base URI of the document with [HttpApiProblem::resolve_type_url].
+//!
+//! ## Type URLs derived from status codes
+//!
+//! [HttpApiProblem::with_title_and_type] and the conversions of an `ApiError`
+//! set a `type_url` derived from the status code. By default this is
+//! `https://httpstatuses.com/<code>`. Use [StatusTypeUrl::set_default] to
+//! use a different base, MDN, `about:blank` or no `type_url` at all.
+//!
+//! ## Problem Types
+//!
+//! A [ProblemType] defines the `type` URL, the fixed title and the default
+//! status of a problem type. A [ProblemTypeRegistry] holds the problem types
+//! of a service, creates problems from them by name and checks that problems
+//! are consistent with the registered definition of their type.
+//!
+//! [ProblemTypeDocs] generates Markdown or HTML documentation pages for the
+//! registered problem types and an index page, so that `type` URLs can
+//! resolve to documentation. A [ProblemTypeDocsHandler] serves them from the
+//! application at the paths of the `type` URLs, as HTML to browsers and as JSON
+//! to machines. It has adapters for axum (feature `axum-extract`) and actix-web.
+//!
+//! ## Compliance
+//!
+//! Problems and their deserialization are lenient. [HttpApiProblem::validate]
+//! reports `type` and `instance` members which are not URI references, a
+//! `status` which is not a client or server error, invalid extension member
+//! names and extension members named like standard members as a [ComplianceError].
+//! [HttpApiProblem::validate_response_status] also compares the `status` with
+//! the status of the response. [HttpApiProblem::from_json_str_strict] reports an
+//! invalid `status` instead of dropping it and validates the parsed problem.
+//!
+//! ## Content Negotiation
+//!
+//! The framework integrations always respond with `application/problem+json`.
+//! A [Negotiator] selects the format based on the `Accept` header of the request
+//! instead. Supported are `application/problem+json`, `application/json`,
+//! `application/problem+xml` (with the feature `xml`), `text/html` and `text/plain`.
+//!
+//! ## Invalid Parameters
+//!
+//! A [ValidationProblem] collects [InvalidParam]s identified by a query
+//! parameter name or a [JSON Pointer](https://tools.ietf.org/html/rfc6901)
+//! and renders them as the extension member `invalid-params`.
+//! Use [HttpApiProblem::invalid_params] to read them on the client side.
+//!
 //! ## Features
 //!
 //! ### JsonSchema
@@ -78,6 +146,45 @@
 //! The feature `json-schema` enables a derived implementation for
 //! JsonSchema, via `schemars`.
 //!
+//! ### utoipa
+//!
+//! The feature `utoipa` implements `ToSchema` for `HttpApiProblem` (with
+//! typed extension members implementing `ToSchema`) and `IntoResponses` for `HttpApiProblem` and `ApiError`. [ProblemResponses]
+//! documents problem responses with examples built from problems or `ApiError`s.
+//!
+//! ### aide
+//!
+//! The feature `aide` (which implies `json-schema`) implements `OperationOutput`
+//! for `HttpApiProblem` and `ApiError`. Handlers returning `Result<T, ApiError>`
+//! document an `application/problem+json` `default` response referencing the
+//! schema of the `json-schema` feature.
+//!
+//! ### XML
+//!
+//! The feature `xml` enables serializing to and parsing from the
+//! `application/problem+xml` format described in
+//! [RFC9457, Appendix B](https://www.rfc-editor.org/rfc/rfc9457#appendix-B).
+//!
+//! ### Validation
+//!
+//! The features `validator` and `garde` convert the validation errors of
+//! these crates into a [ValidationProblem], an `HttpApiProblem` and an `ApiError`.
+//! The status is `422 - Unprocessable Entity`. Use [ValidationProblem::status]
+//! for a different one.
+//!
+//! ### Tower
+//!
+//! The feature `tower` enables the [ProblemLayer] which replaces the body of
+//! `4xx` and `5xx` responses, that are not already problems, with an `HttpApiProblem`.
+//!
+//! ### Catching Panics
+//!
+//! The feature `catch-panic` (which implies `api-error`) enables
+//! [ApiError::from_panic]. Together with the features `tower` or `actix-web`
+//! it enables middlewares which respond with a `500 - Internal Server Error`
+//! problem if a handler panics. The details of the panic are not exposed
+//! to the client but passed to a callback as [PanicDetails].
+//!
 //! ### Web Frameworks
 //!
 //! There are multiple features to integrate with web frameworks:
@@ -89,15 +196,65 @@
 //! * `salvo`
 //! * `tide`
 //! * `rocket (v0.5.0-rc1)`
+//! * `poem`
+//! * `ntex`
 //!
 //! These mainly convert the `HttpApiProblem` to response types of
 //! the frameworks and implement traits to integrate with the frameworks
 //! error handling.
 //!
+//! With the feature `axum-extract` (which depends on `axum` and `axum-extra`
+//! instead of only `axum-core`) the rejections of axum's `Json`, `Path`, `Query`,
+//! `Form` and `TypedHeader` extractors convert into problems and the [Problem]
+//! extractor wraps other extractors to reject with a problem.
+//!
+//! With the feature `actix-web` the errors of actix-web's `Json`, `Query`, `Path`
+//! and `Form` extractors convert into problems. [json_error_handler],
+//! [query_error_handler], [path_error_handler] and [form_error_handler] can be set
+//! on the configs of these extractors and [problem_error_handlers] turns all other
+//! error responses into problems.
+//!
+//! With the feature `warp` an `HttpApiProblem` is a `warp::Reply` and
+//! [recover] responds to rejections, including warp's own rejections, with problems.
+//!
+//! With the feature `rocket` an `ApiError` is a `Responder`, [problem_catchers]
+//! responds to all errors with problems and rocket's form `Errors` convert into problems.
+//!
+//! With the feature `poem` an `HttpApiProblem` and an `ApiError` implement poem's
+//! `ResponseError` and `IntoResponse` and a `poem::Error` converts into both.
+//! The feature `poem-openapi` (which implies `poem`) implements `poem_openapi`'s
+//! `ApiResponse` so that problems are documented in generated specs.
+//!
+//! With the feature `ntex` an `HttpApiProblem` and an `ApiError` implement ntex's
+//! `WebResponseError` and the errors of ntex's extractors and `web::Error`
+//! convert into problems. The feature `ntex` does not choose a runtime for
+//! ntex, the feature `ntex-tokio` (which implies `ntex`) runs it on tokio.
+//!
 //! Additionally, the feature `rocket-okapi` (which implies the features
 //! `rocket` and `json-schema`) implements `rocket_okapi`'s `OpenApiResponder`
 //! for the json schema generated by the `json-schema` feature.
 //!
+//! ### gRPC
+//!
+//! The feature `tonic` converts an `HttpApiProblem` and an `ApiError` into a
+//! `tonic::Status` and back. The status code is mapped to a gRPC code and the
+//! problem travels in the binary details of the `tonic::Status`.
+//!
+//! ### Testing
+//!
+//! The feature `testing` enables the module [testing] with [testing::ProblemAssert]
+//! and the [assert_problem!] macro which check that responses of hyper, axum,
+//! actix-web and (with the feature `reqwest`) reqwest are the expected problems.
+//!
+//! ### Property Based Testing
+//!
+//! The feature `proptest` implements `proptest`'s `Arbitrary` for `HttpApiProblem`
+//! and `ApiError` and the feature `quickcheck` implements `quickcheck`'s `Arbitrary`
+//! for `HttpApiProblem`. The generated problems have real status codes, URI like
+//! `type` and `instance` members and nested JSON extension members without
+//! reserved keys. `ApiError` is not `Clone` and therefore does not implement
+//! `quickcheck`'s `Arbitrary`.
+//!
 //! ### ApiError
 //!
 //! The feature `api-error` enables a structure which can be
@@ -118,19 +275,96 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+mod compliance;
+mod negotiation;
+mod problem_type;
+mod problem_type_docs;
+mod problem_type_docs_handler;
+mod status_type_url;
+mod uri;
+mod validation;
+
 #[cfg(feature = "api-error")]
 mod api_error;
 #[cfg(feature = "api-error")]
 pub use api_error::*;
 
+pub use compliance::*;
+pub use negotiation::*;
+pub use problem_type::*;
+pub use problem_type_docs::*;
+pub use problem_type_docs_handler::*;
+pub use status_type_url::*;
+pub use validation::*;
+
+#[cfg(feature = "rocket")]
+mod rocket_catchers;
+#[cfg(feature = "rocket")]
+pub use rocket_catchers::*;
+
+#[cfg(feature = "warp")]
+mod warp_recover;
+#[cfg(feature = "warp")]
+pub use warp_recover::*;
+
+#[cfg(feature = "xml")]
+mod xml;
+#[cfg(feature = "xml")]
+pub use xml::*;
+
 #[cfg(feature = "json-schema")]
-use schemars::JsonSchema;
+mod json_schema;
+
+#[cfg(any(feature = "json-schema", feature = "poem-openapi", feature = "utoipa"))]
+mod schema_descriptions;
+
+#[cfg(feature = "poem-openapi")]
+mod poem_api_response;
+
+#[cfg(feature = "aide")]
+mod aide_output;
+
+#[cfg(feature = "utoipa")]
+mod utoipa_schema;
+#[cfg(feature = "utoipa")]
+pub use utoipa_schema::*;
 
 #[cfg(feature = "actix-web")]
-use actix_web_crate as actix_web;
+mod actix_errors;
+#[cfg(feature = "actix-web")]
+pub use actix_errors::*;
 
-#[cfg(feature = "axum")]
-use axum_core;
+#[cfg(feature = "ntex")]
+mod ntex_errors;
+
+#[cfg(feature = "tonic")]
+mod tonic_status;
+#[cfg(feature = "tonic")]
+pub use tonic_status::*;
+
+#[cfg(feature = "axum-extract")]
+mod axum_extract;
+#[cfg(feature = "axum-extract")]
+pub use axum_extract::*;
+
+#[cfg(feature = "catch-panic")]
+mod catch_panic;
+#[cfg(feature = "catch-panic")]
+pub use catch_panic::*;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod arbitrary;
+
+#[cfg(feature = "tower")]
+mod problem_layer;
+#[cfg(feature = "tower")]
+pub use problem_layer::*;
+
+#[cfg(feature = "actix-web")]
+use actix_web_crate as actix_web;
 
 pub use http::status::{InvalidStatusCode, StatusCode};
 
@@ -139,8 +373,21 @@ pub use http::status::{InvalidStatusCode, StatusCode};
 /// "application/problem+json"
 pub static PROBLEM_JSON_MEDIA_TYPE: &str = "application/problem+json";
 
+/// The recommended media type when serialized to XML
+///
+/// "application/problem+xml"
+pub static PROBLEM_XML_MEDIA_TYPE: &str = "application/problem+xml";
+
+/// The value a missing `type` member is assumed to have
+///
+/// "about:blank"
+///
+/// See [RFC9457, Section 4.2.1](https://www.rfc-editor.org/rfc/rfc9457#section-4.2.1)
+pub static ABOUT_BLANK: &str = "about:blank";
+
 /// Description of a problem that can be returned by an HTTP API
-/// based on [RFC7807](https://tools.ietf.org/html/rfc7807)
+/// based on [RFC9457](https://www.rfc-editor.org/rfc/rfc9457)
+/// (formerly [RFC7807](https://tools.ietf.org/html/rfc7807))
 ///
 /// # Example
 ///
@@ -175,38 +422,71 @@ pub static PROBLEM_JSON_MEDIA_TYPE: &str = "application/problem+json";
 /// [StatusCode] contained. In this case the `status` field will be empty.
 /// This is a trade off so that the recipient does not have to deal with
 /// another error and can still have access to the remaining fields of the
-/// struct.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
-#[cfg_attr(
-    feature = "json-schema",
-    schemars(
-        description = "Description of a problem that can be returned by an HTTP API based on [RFC7807](https://tools.ietf.org/html/rfc7807)"
-    )
-)]
-pub struct HttpApiProblem {
+/// struct. Use [HttpApiProblem::from_json_str_strict] to get an error instead
+/// and [HttpApiProblem::validate] to check a problem for compliance with RFC 9457.
+///
+/// # Extension Members
+///
+/// Extension members can either be added untyped as JSON values (see
+/// [HttpApiProblem::value]) or typed via the type parameter `Ext`.
+/// `Ext` is flattened into the problem document and therefore has to
+/// serialize to a map. The default `()` adds no members at all.
+/// Serializing a problem whose `Ext` does not serialize to a map fails
+/// and the `to_*_response` methods respond with a
+/// `500 - Internal Server Error` problem instead.
+///
+/// A member is never serialized twice: typed members replace untyped
+/// members with the same name and extension members named like a
+/// standard member (e.g. a field `status` of `Ext`) are left out.
+/// [HttpApiProblem::validate] reports the latter.
+///
+/// ```rust
+/// use http_api_problem::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct OutOfCredit {
+///     balance: u32,
+///     accounts: Vec<String>,
+/// }
+///
+/// let p = HttpApiProblem::new(StatusCode::FORBIDDEN)
+///     .title("You do not have enough credit.")
+///     .with_extension_members(OutOfCredit {
+///         balance: 30,
+///         accounts: vec!["/account/12345".to_string()],
+///     });
+///
+/// let json = p.json_string();
+///
+/// let parsed: HttpApiProblem<OutOfCredit> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(30, parsed.extension_members.balance);
+///
+/// // ... or from the untyped form
+/// let untyped: HttpApiProblem = serde_json::from_str(&json).unwrap();
+/// let typed = untyped.into_typed::<OutOfCredit>().unwrap();
+/// assert_eq!(30, typed.extension_members.balance);
+/// assert!(typed.additional_fields().is_empty());
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct HttpApiProblem<Ext = ()> {
     /// A URI reference [RFC3986](https://tools.ietf.org/html/rfc3986) that identifies the
     /// problem type.  This specification encourages that, when
     /// dereferenced, it provide human-readable documentation for the
     /// problem type (e.g., using HTML [W3C.REC-html5-20141028]).  When
     /// this member is not present, its value is assumed to be
     /// "about:blank".
+    ///
+    /// It may be a relative reference which has to be resolved
+    /// against the base URI of the document
+    /// (see [HttpApiProblem::resolve_type_url]).
     #[serde(rename = "type")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[cfg_attr(
-        feature = "json-schema",
-        schemars(
-            description = "A [RFC3986 URI reference](https://tools.ietf.org/html/rfc3986) that identifies the problem type. When dereferenced, it may provide human-readable documentation for the problem type."
-        )
-    )]
     pub type_url: Option<String>,
 
     /// The HTTP status code [RFC7231, Section 6](https://tools.ietf.org/html/rfc7231#section-6)
     /// generated by the origin server for this occurrence of the problem.
     #[serde(default)]
-    #[serde(with = "custom_http_status_serialization")]
-    #[cfg_attr(feature = "json-schema", schemars(with = "u16"))]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "custom_http_status_serialization::deserialize")]
     pub status: Option<StatusCode>,
 
     /// A short, human-readable summary of the problem
@@ -214,26 +494,23 @@ pub struct HttpApiProblem {
     /// problem, except for purposes of localization (e.g., using
     /// proactive content negotiation;
     /// see [RFC7231, Section 3.4](https://tools.ietf.org/html/rfc7231#section-3.4).
-    #[cfg_attr(
-        feature = "json-schema",
-        schemars(
-            description = "A short, human-readable summary of the problem type. It SHOULD NOT change from occurrence to occurrence of the problem."
-        )
-    )]
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
 
     /// A human-readable explanation specific to this
     /// occurrence of the problem.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub detail: Option<String>,
 
     /// A URI reference that identifies the specific
     /// occurrence of the problem.  It may or may not yield further
     /// information if dereferenced.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub instance: Option<String>,
 
+    /// Typed extension members
+    ///
+    /// These get flattened into the JSON on top level.
+    #[serde(flatten)]
+    pub extension_members: Ext,
+
     /// Additional fields that must be JSON values
     ///
     /// These values get serialized into the JSON
@@ -340,6 +617,9 @@ impl HttpApiProblem {
     /// Creates a new instance with the `title` and `type_url` derived from the
     /// [StatusCode].
     ///
+    /// The `type_url` is derived as configured with [StatusTypeUrl::set_default]
+    /// which defaults to `https://httpstatuses.com/<code>`.
+    ///
     /// #Example
     ///
     /// ```rust
@@ -354,8 +634,31 @@ impl HttpApiProblem {
     /// assert_eq!(None, p.instance);
     /// ```
     pub fn with_title_and_type<T: Into<StatusCode>>(status: T) -> Self {
+        Self::with_title_and_type_from(status, &StatusTypeUrl::get_default())
+    }
+
+    /// Creates a new instance with the `title` derived from the [StatusCode]
+    /// and the `type_url` derived as defined by the given [StatusTypeUrl].
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::with_title_and_type_from(StatusCode::SERVICE_UNAVAILABLE, &StatusTypeUrl::AboutBlank);
+    ///
+    /// assert_eq!(Some(StatusCode::SERVICE_UNAVAILABLE), p.status);
+    /// assert_eq!(Some("Service Unavailable"), p.title.as_deref());
+    /// assert_eq!(Some("about:blank".to_string()), p.type_url);
+    /// ```
+    pub fn with_title_and_type_from<T: Into<StatusCode>>(
+        status: T,
+        status_type_url: &StatusTypeUrl,
+    ) -> Self {
         let status = status.into();
-        Self::with_title(status).type_url(format!("https://httpstatuses.com/{}", status.as_u16()))
+        let mut problem = Self::with_title(status);
+        problem.type_url = status_type_url.type_url(status);
+        problem
     }
 
     /// Creates a new instance with the `title` and `type_url` derived from the
@@ -387,6 +690,53 @@ impl HttpApiProblem {
         Ok(Self::with_title_and_type(status))
     }
 
+    /// Creates a new instance with the `type_url` set to `about:blank` and the
+    /// `title` derived from the [StatusCode].
+    ///
+    /// This is what [RFC9457, Section 4.2.1](https://www.rfc-editor.org/rfc/rfc9457#section-4.2.1)
+    /// recommends for problems which have no semantics beyond the HTTP status code.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::about_blank(StatusCode::SERVICE_UNAVAILABLE);
+    ///
+    /// assert_eq!(Some(StatusCode::SERVICE_UNAVAILABLE), p.status);
+    /// assert_eq!(Some("Service Unavailable"), p.title.as_deref());
+    /// assert_eq!(None, p.detail);
+    /// assert_eq!(Some("about:blank".to_string()), p.type_url);
+    /// assert_eq!(None, p.instance);
+    /// ```
+    pub fn about_blank<T: Into<StatusCode>>(status: T) -> Self {
+        Self::with_title(status).type_url(ABOUT_BLANK)
+    }
+
+    /// Creates a new instance with the `type_url` set to `about:blank` and the
+    /// `title` derived from the [StatusCode].
+    ///
+    /// Fails if the argument can not be converted into a [StatusCode].
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::try_about_blank(503).unwrap();
+    ///
+    /// assert_eq!(Some(StatusCode::SERVICE_UNAVAILABLE), p.status);
+    /// assert_eq!(Some("Service Unavailable"), p.title.as_deref());
+    /// assert_eq!(Some("about:blank".to_string()), p.type_url);
+    /// ```
+    pub fn try_about_blank<T: TryInto<StatusCode>>(status: T) -> Result<Self, InvalidStatusCode>
+    where
+        T::Error: Into<InvalidStatusCode>,
+    {
+        let status = status.try_into().map_err(|e| e.into())?;
+        Ok(Self::about_blank(status))
+    }
+
     /// Creates a new instance without any field set.
     ///
     /// Prefer to use one of the other constructors which
@@ -401,10 +751,39 @@ impl HttpApiProblem {
             title: None,
             detail: None,
             instance: None,
+            extension_members: (),
             additional_fields: Default::default(),
         }
     }
 
+    /// Converts the untyped extension members into the typed extension members `E`.
+    ///
+    /// All members consumed by `E` are removed from the [additional fields](HttpApiProblem::additional_fields).
+    ///
+    /// Fails if the additional fields can not be deserialized into `E`.
+    pub fn into_typed<E>(mut self) -> Result<HttpApiProblem<E>, serde_json::Error>
+    where
+        E: Serialize + DeserializeOwned,
+    {
+        let members: serde_json::Map<String, Value> = self
+            .additional_fields
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        let extension_members: E = serde_json::from_value(Value::Object(members))?;
+
+        if let Value::Object(consumed) = serde_json::to_value(&extension_members)? {
+            for key in consumed.keys() {
+                self.additional_fields.remove(key);
+            }
+        }
+
+        Ok(self.with_extension_members(extension_members))
+    }
+}
+
+impl<Ext> HttpApiProblem<Ext> {
     /// Sets the `status`
     ///
     /// #Example
@@ -508,7 +887,7 @@ impl HttpApiProblem {
     /// assert_eq!(None, p.type_url);
     /// assert_eq!(None, p.instance);
     /// ```
-    pub fn detail<T: Into<String>>(mut self, detail: T) -> HttpApiProblem {
+    pub fn detail<T: Into<String>>(mut self, detail: T) -> Self {
         self.detail = Some(detail.into());
         self
     }
@@ -528,11 +907,117 @@ impl HttpApiProblem {
     /// assert_eq!(None, p.type_url);
     /// assert_eq!(Some("/account/1234/withdraw".to_string()), p.instance);
     /// ```
-    pub fn instance<T: Into<String>>(mut self, instance: T) -> HttpApiProblem {
+    pub fn instance<T: Into<String>>(mut self, instance: T) -> Self {
         self.instance = Some(instance.into());
         self
     }
 
+    /// Replaces the typed extension members
+    ///
+    /// The untyped [additional fields](HttpApiProblem::additional_fields) are kept.
+    pub fn with_extension_members<E>(self, extension_members: E) -> HttpApiProblem<E> {
+        HttpApiProblem {
+            type_url: self.type_url,
+            status: self.status,
+            title: self.title,
+            detail: self.detail,
+            instance: self.instance,
+            extension_members,
+            additional_fields: self.additional_fields,
+        }
+    }
+
+    /// Returns the `type_url` or `about:blank` if it is not set.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::NOT_FOUND);
+    /// assert_eq!("about:blank", p.type_url_or_about_blank());
+    ///
+    /// let p = p.type_url("/probs/out-of-credit");
+    /// assert_eq!("/probs/out-of-credit", p.type_url_or_about_blank());
+    /// ```
+    pub fn type_url_or_about_blank(&self) -> &str {
+        self.type_url.as_deref().unwrap_or(ABOUT_BLANK)
+    }
+
+    /// Returns `true` if the `type_url` is `about:blank` or not set.
+    pub fn is_about_blank(&self) -> bool {
+        self.type_url_or_about_blank() == ABOUT_BLANK
+    }
+
+    /// Applies the rules of [RFC9457](https://www.rfc-editor.org/rfc/rfc9457).
+    ///
+    /// * If there is no `type_url` it will be set to `about:blank`
+    /// * If the `type_url` is `about:blank`, there is a `status` but no `title`
+    ///   the `title` will be set to the reason phrase of the `status`
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::NOT_FOUND).into_rfc9457();
+    ///
+    /// assert_eq!(Some(StatusCode::NOT_FOUND), p.status);
+    /// assert_eq!(Some("Not Found"), p.title.as_deref());
+    /// assert_eq!(Some("about:blank".to_string()), p.type_url);
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::FORBIDDEN)
+    ///     .type_url("/probs/out-of-credit")
+    ///     .into_rfc9457();
+    ///
+    /// assert_eq!(None, p.title);
+    /// assert_eq!(Some("/probs/out-of-credit".to_string()), p.type_url);
+    /// ```
+    pub fn into_rfc9457(mut self) -> Self {
+        if self.type_url.is_none() {
+            self.type_url = Some(ABOUT_BLANK.to_string());
+        }
+
+        if self.is_about_blank() && self.title.is_none() {
+            if let Some(reason) = self.status.and_then(|s| s.canonical_reason()) {
+                self.title = Some(reason.to_string());
+            }
+        }
+
+        self
+    }
+
+    /// Resolves the `type_url` against the given base URI.
+    ///
+    /// The `type_url` may be a relative reference. In that case it
+    /// has to be resolved against the base URI of the document
+    /// which usually is the URI of the request.
+    /// If there is no `type_url`, `about:blank` is returned.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::FORBIDDEN).type_url("../probs/out-of-credit");
+    ///
+    /// assert_eq!(
+    ///     "https://example.com/probs/out-of-credit",
+    ///     p.resolve_type_url("https://example.com/account/12345")
+    /// );
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::FORBIDDEN);
+    /// assert_eq!("about:blank", p.resolve_type_url("https://example.com/account/12345"));
+    /// ```
+    pub fn resolve_type_url(&self, base: &str) -> String {
+        let type_url = self.type_url_or_about_blank();
+        if uri::is_absolute(type_url) {
+            type_url.to_string()
+        } else {
+            uri::resolve(base, type_url)
+        }
+    }
+
     /// Add a value that must be serializable.
     ///
     /// The key must not be one of the field names of this struct.
@@ -604,7 +1089,16 @@ impl HttpApiProblem {
         V: Serialize,
         K: Into<String>,
     {
-        let key: String = key.into();
+        let serialized = serde_json::to_value(value).map_err(|err| err.to_string())?;
+        self.try_insert_value(key.into(), serialized)
+    }
+
+    /// Inserts an already serialized value unless the key is reserved
+    fn try_insert_value(
+        &mut self,
+        key: String,
+        value: Value,
+    ) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
         match key.as_ref() {
             "type" => return Err("'type' is a reserved field name".into()),
             "status" => return Err("'status' is a reserved field name".into()),
@@ -616,8 +1110,7 @@ impl HttpApiProblem {
             }
             _ => (),
         }
-        let serialized = serde_json::to_value(value).map_err(|err| err.to_string())?;
-        self.additional_fields.insert(key, serialized);
+        self.additional_fields.insert(key, value);
         Ok(())
     }
 
@@ -633,6 +1126,10 @@ impl HttpApiProblem {
     ///
     /// If the key does not exist or the field is not deserializable to
     /// the target type `None` is returned
+    ///
+    /// Unlike [HttpApiProblem::try_set_value] this does not prevent
+    /// inserting the names of the standard members like `status`. These
+    /// are not serialized. Use [HttpApiProblem::validate] to detect them.
     pub fn additional_fields_mut(&mut self) -> &mut HashMap<String, Value> {
         &mut self.additional_fields
     }
@@ -649,14 +1146,165 @@ impl HttpApiProblem {
         self.additional_fields.get(key)
     }
 
+    #[allow(dead_code)]
+    fn status_or_internal_server_error(&self) -> StatusCode {
+        self.status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    #[allow(dead_code)]
+    fn status_code_or_internal_server_error(&self) -> u16 {
+        self.status_or_internal_server_error().as_u16()
+    }
+
+    // Deprecations
+
+    #[deprecated(since = "0.50.0", note = "please use `status` instead")]
+    pub fn set_status<T: Into<StatusCode>>(self, status: T) -> Self {
+        self.status(status)
+    }
+    #[deprecated(since = "0.50.0", note = "please use `title` instead")]
+    pub fn set_title<T: Into<String>>(self, title: T) -> Self {
+        self.title(title)
+    }
+    #[deprecated(since = "0.50.0", note = "please use `detail` instead")]
+    pub fn set_detail<T: Into<String>>(self, detail: T) -> Self {
+        self.detail(detail)
+    }
+    #[deprecated(since = "0.50.0", note = "please use `type_url` instead")]
+    pub fn set_type_url<T: Into<String>>(self, type_url: T) -> Self {
+        self.type_url(type_url)
+    }
+    #[deprecated(since = "0.50.0", note = "please use `instance` instead")]
+    pub fn set_instance<T: Into<String>>(self, instance: T) -> Self {
+        self.instance(instance)
+    }
+}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Converts the typed extension members into untyped
+    /// [additional fields](HttpApiProblem::additional_fields).
+    ///
+    /// Fails if the extension members do not serialize to a map.
+    pub fn into_untyped(self) -> Result<HttpApiProblem, serde_json::Error> {
+        let members = serde_json::to_value(&self.extension_members)?;
+        let mut problem = self.with_extension_members(());
+
+        match members {
+            Value::Object(members) => problem.additional_fields.extend(members),
+            Value::Null => {}
+            _ => {
+                return Err(serde::ser::Error::custom(
+                    "extension members must serialize to a map",
+                ))
+            }
+        }
+
+        Ok(problem)
+    }
+
+    /// Returns all extension members, typed and untyped, as JSON values.
+    ///
+    /// Typed members take precedence over untyped members with the same name.
+    /// Members named like the standard members are left out.
+    ///
+    /// Fails if the typed extension members do not serialize to a map
+    /// (or `null`).
+    pub fn extension_members_json(
+        &self,
+    ) -> Result<serde_json::Map<String, Value>, serde_json::Error> {
+        let mut members: serde_json::Map<String, Value> =
+            compliance::without_standard_members(&self.additional_fields)
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+        match serde_json::to_value(&self.extension_members)? {
+            Value::Object(typed) => members.extend(
+                typed
+                    .into_iter()
+                    .filter(|(name, _)| !compliance::is_standard_member(name)),
+            ),
+            Value::Null => {}
+            other => {
+                return Err(serde::ser::Error::custom(format!(
+                    "the extension members have to serialize to a map but got {}",
+                    other
+                )))
+            }
+        }
+
+        Ok(members)
+    }
+
     /// Serialize to a JSON `Vec<u8>`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the typed extension members do not serialize to a map.
+    /// Use [HttpApiProblem::try_json_bytes] to handle this case.
     pub fn json_bytes(&self) -> Vec<u8> {
-        serde_json::to_vec(self).unwrap()
+        self.try_json_bytes().unwrap()
     }
 
     /// Serialize to a JSON `String`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the typed extension members do not serialize to a map.
+    /// Use [HttpApiProblem::try_json_string] to handle this case.
     pub fn json_string(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap()
+        self.try_json_string().unwrap()
+    }
+
+    /// Serialize to a JSON `Vec<u8>`
+    ///
+    /// Fails if the typed extension members do not serialize to a map.
+    pub fn try_json_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+
+    /// Serialize to a JSON `String`
+    ///
+    /// Fails if the typed extension members do not serialize to a map.
+    pub fn try_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// The status and the JSON body of a response.
+    ///
+    /// A problem which can not be serialized becomes a
+    /// `500 - Internal Server Error` problem.
+    #[cfg(any(
+        feature = "axum",
+        feature = "actix-web",
+        feature = "ntex",
+        feature = "warp",
+        feature = "rocket",
+        feature = "tide",
+        feature = "poem"
+    ))]
+    fn response_json_bytes(&self) -> (StatusCode, Vec<u8>) {
+        match self.try_json_bytes() {
+            Ok(json) => (self.status_or_internal_server_error(), json),
+            Err(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                unserializable_problem().json_bytes(),
+            ),
+        }
+    }
+
+    /// The status and the JSON body of a response.
+    ///
+    /// A problem which can not be serialized becomes a
+    /// `500 - Internal Server Error` problem.
+    #[cfg(any(feature = "hyper", feature = "salvo"))]
+    fn response_json_string(&self) -> (StatusCode, String) {
+        match self.try_json_string() {
+            Ok(json) => (self.status_or_internal_server_error(), json),
+            Err(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                unserializable_problem().json_string(),
+            ),
+        }
     }
 
     /// Creates a [hyper] response.
@@ -670,7 +1318,7 @@ impl HttpApiProblem {
         use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
         use hyper::*;
 
-        let json = self.json_string();
+        let (status, json) = self.response_json_string();
         let length = json.len() as u64;
 
         let (mut parts, body) = Response::new(json).into_parts();
@@ -683,8 +1331,7 @@ impl HttpApiProblem {
             CONTENT_LENGTH,
             HeaderValue::from_str(&length.to_string()).unwrap(),
         );
-        parts.status = self
-            .status_or_internal_server_error()
+        parts.status = status
             .as_u16()
             .try_into()
             .unwrap_or(hyper::StatusCode::INTERNAL_SERVER_ERROR);
@@ -703,14 +1350,12 @@ impl HttpApiProblem {
         use axum_core::response::IntoResponse;
         use http::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
 
-        let json = self.json_bytes();
+        let (status, json) = self.response_json_bytes();
         let length = json.len() as u64;
 
-        let status = self.status_or_internal_server_error();
-
         let mut response = (status, json).into_response();
 
-        *response.status_mut() = self.status_or_internal_server_error();
+        *response.status_mut() = status;
 
         response.headers_mut().insert(
             CONTENT_TYPE,
@@ -733,12 +1378,10 @@ impl HttpApiProblem {
     /// Requires the `actix-web` feature
     #[cfg(feature = "actix-web")]
     pub fn to_actix_response(&self) -> actix_web::HttpResponse {
-        let effective_status = self.status_or_internal_server_error();
+        let (effective_status, json) = self.response_json_bytes();
         let actix_status = actix_web::http::StatusCode::from_u16(effective_status.as_u16())
             .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
 
-        let json = self.json_bytes();
-
         actix_web::HttpResponse::build(actix_status)
             .append_header((
                 actix_web::http::header::CONTENT_TYPE,
@@ -747,6 +1390,51 @@ impl HttpApiProblem {
             .body(json)
     }
 
+    /// Creates an `ntex` response.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `ntex` feature
+    #[cfg(feature = "ntex")]
+    pub fn to_ntex_response(&self) -> ntex::web::HttpResponse {
+        let (status, json) = self.response_json_bytes();
+
+        ntex::web::HttpResponse::build(status)
+            .set_header(http::header::CONTENT_TYPE, PROBLEM_JSON_MEDIA_TYPE)
+            .body(json)
+    }
+
+    /// Creates a `warp` response.
+    ///
+    /// warp uses `http` 0.2, so the status is converted by its code.
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `warp` feature
+    #[cfg(feature = "warp")]
+    pub fn to_warp_response(&self) -> warp::reply::Response {
+        use warp::http::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+
+        let (status, json) = self.response_json_bytes();
+        let length = json.len() as u64;
+
+        let mut response = warp::reply::Response::new(json.into());
+
+        *response.status_mut() = warp::http::StatusCode::from_u16(status.as_u16())
+            .unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_JSON_MEDIA_TYPE),
+        );
+        response.headers_mut().insert(
+            CONTENT_LENGTH,
+            HeaderValue::from_str(&length.to_string()).unwrap(),
+        );
+
+        response
+    }
+
     /// Creates a `rocket` response.
     ///
     /// If status is `None` `500 - Internal Server Error` is the
@@ -761,10 +1449,10 @@ impl HttpApiProblem {
         use std::io::Cursor;
 
         let content_type: ContentType = PROBLEM_JSON_MEDIA_TYPE.parse().unwrap();
-        let json = self.json_bytes();
+        let (status, json) = self.response_json_bytes();
         let response = Response::build()
             .status(Status {
-                code: self.status_code_or_internal_server_error(),
+                code: status.as_u16(),
             })
             .sized_body(json.len(), Cursor::new(json))
             .header(content_type)
@@ -784,7 +1472,7 @@ impl HttpApiProblem {
         use salvo::hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
         use salvo::hyper::*;
 
-        let json = self.json_string();
+        let (status, json) = self.response_json_string();
         let length = json.len() as u64;
 
         let (mut parts, body) = Response::new(json).into_parts();
@@ -797,8 +1485,7 @@ impl HttpApiProblem {
             CONTENT_LENGTH,
             HeaderValue::from_str(&length.to_string()).unwrap(),
         );
-        parts.status = self
-            .status_or_internal_server_error()
+        parts.status = status
             .as_u16()
             .try_into()
             .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
@@ -814,26 +1501,34 @@ impl HttpApiProblem {
     /// Requires the `tide` feature
     #[cfg(feature = "tide")]
     pub fn to_tide_response(&self) -> tide::Response {
-        let json = self.json_bytes();
+        let (status, json) = self.response_json_bytes();
         let length = json.len() as u64;
 
-        tide::Response::builder(self.status_code_or_internal_server_error())
+        tide::Response::builder(status.as_u16())
             .body(json)
             .header("Content-Length", length.to_string())
             .content_type(PROBLEM_JSON_MEDIA_TYPE)
             .build()
     }
 
-    #[allow(dead_code)]
-    fn status_or_internal_server_error(&self) -> StatusCode {
-        self.status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
-    }
+    /// Creates a [poem] response.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `poem` feature
+    #[cfg(feature = "poem")]
+    pub fn to_poem_response(&self) -> poem::Response {
+        let (status, json) = self.response_json_bytes();
 
-    #[allow(dead_code)]
-    fn status_code_or_internal_server_error(&self) -> u16 {
-        self.status_or_internal_server_error().as_u16()
+        poem::Response::builder()
+            .status(status)
+            .content_type(PROBLEM_JSON_MEDIA_TYPE)
+            .body(json)
     }
+}
 
+impl HttpApiProblem {
     // Deprecations
 
     #[deprecated(since = "0.50.0", note = "please use `with_title` instead")]
@@ -844,29 +1539,9 @@ impl HttpApiProblem {
     pub fn with_title_and_type_from_status<T: Into<StatusCode>>(status: T) -> Self {
         Self::with_title_and_type(status)
     }
-    #[deprecated(since = "0.50.0", note = "please use `status` instead")]
-    pub fn set_status<T: Into<StatusCode>>(self, status: T) -> Self {
-        self.status(status)
-    }
-    #[deprecated(since = "0.50.0", note = "please use `title` instead")]
-    pub fn set_title<T: Into<String>>(self, title: T) -> Self {
-        self.title(title)
-    }
-    #[deprecated(since = "0.50.0", note = "please use `detail` instead")]
-    pub fn set_detail<T: Into<String>>(self, detail: T) -> Self {
-        self.detail(detail)
-    }
-    #[deprecated(since = "0.50.0", note = "please use `type_url` instead")]
-    pub fn set_type_url<T: Into<String>>(self, type_url: T) -> Self {
-        self.type_url(type_url)
-    }
-    #[deprecated(since = "0.50.0", note = "please use `instance` instead")]
-    pub fn set_instance<T: Into<String>>(self, instance: T) -> Self {
-        self.instance(instance)
-    }
 }
 
-impl fmt::Display for HttpApiProblem {
+impl<Ext> fmt::Display for HttpApiProblem<Ext> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(status) = self.status {
             write!(f, "{}", status)?;
@@ -889,7 +1564,7 @@ impl fmt::Display for HttpApiProblem {
     }
 }
 
-impl Error for HttpApiProblem {
+impl<Ext: fmt::Debug> Error for HttpApiProblem<Ext> {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
     }
@@ -919,8 +1594,8 @@ pub fn into_hyper_response<T: Into<HttpApiProblem>>(what: T) -> hyper::Response<
 }
 
 #[cfg(feature = "hyper")]
-impl From<HttpApiProblem> for hyper::Response<String> {
-    fn from(problem: HttpApiProblem) -> hyper::Response<String> {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for hyper::Response<String> {
+    fn from(problem: HttpApiProblem<Ext>) -> hyper::Response<String> {
         problem.to_hyper_response()
     }
 }
@@ -939,14 +1614,14 @@ pub fn into_axum_response<T: Into<HttpApiProblem>>(what: T) -> axum_core::respon
 }
 
 #[cfg(feature = "axum")]
-impl From<HttpApiProblem> for axum_core::response::Response {
-    fn from(problem: HttpApiProblem) -> axum_core::response::Response {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for axum_core::response::Response {
+    fn from(problem: HttpApiProblem<Ext>) -> axum_core::response::Response {
         problem.to_axum_response()
     }
 }
 
 #[cfg(feature = "axum")]
-impl axum_core::response::IntoResponse for HttpApiProblem {
+impl<Ext: Serialize> axum_core::response::IntoResponse for HttpApiProblem<Ext> {
     fn into_response(self) -> axum_core::response::Response {
         self.into()
     }
@@ -964,12 +1639,41 @@ pub fn into_actix_response<T: Into<HttpApiProblem>>(what: T) -> actix_web::HttpR
 }
 
 #[cfg(feature = "actix-web")]
-impl From<HttpApiProblem> for actix_web::HttpResponse {
-    fn from(problem: HttpApiProblem) -> actix_web::HttpResponse {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for actix_web::HttpResponse {
+    fn from(problem: HttpApiProblem<Ext>) -> actix_web::HttpResponse {
         problem.to_actix_response()
     }
 }
 
+/// Creates an `ntex::web::HttpResponse` from something that can become an
+/// `HttpApiProblem`.
+///
+/// If status is `None` `500 - Internal Server Error` is the
+/// default.
+#[cfg(feature = "ntex")]
+pub fn into_ntex_response<T: Into<HttpApiProblem>>(what: T) -> ntex::web::HttpResponse {
+    let problem: HttpApiProblem = what.into();
+    problem.to_ntex_response()
+}
+
+#[cfg(feature = "ntex")]
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for ntex::web::HttpResponse {
+    fn from(problem: HttpApiProblem<Ext>) -> ntex::web::HttpResponse {
+        problem.to_ntex_response()
+    }
+}
+
+#[cfg(feature = "ntex")]
+impl<Ext: Serialize + fmt::Debug + 'static> ntex::web::WebResponseError for HttpApiProblem<Ext> {
+    fn status_code(&self) -> StatusCode {
+        self.status_or_internal_server_error()
+    }
+
+    fn error_response(&self, _: &ntex::web::HttpRequest) -> ntex::web::HttpResponse {
+        self.to_ntex_response()
+    }
+}
+
 /// Creates an `rocket::Response` from something that can become an
 /// `HttpApiProblem`.
 ///
@@ -982,26 +1686,28 @@ pub fn into_rocket_response<T: Into<HttpApiProblem>>(what: T) -> ::rocket::Respo
 }
 
 #[cfg(feature = "rocket")]
-impl From<HttpApiProblem> for ::rocket::Response<'static> {
-    fn from(problem: HttpApiProblem) -> ::rocket::Response<'static> {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for ::rocket::Response<'static> {
+    fn from(problem: HttpApiProblem<Ext>) -> ::rocket::Response<'static> {
         problem.to_rocket_response()
     }
 }
 
 #[cfg(feature = "rocket")]
-impl<'r> ::rocket::response::Responder<'r, 'static> for HttpApiProblem {
+impl<'r, Ext: Serialize> ::rocket::response::Responder<'r, 'static> for HttpApiProblem<Ext> {
     fn respond_to(self, _request: &::rocket::Request) -> ::rocket::response::Result<'static> {
         Ok(self.into())
     }
 }
 
 #[cfg(feature = "rocket-okapi")]
-impl rocket_okapi::response::OpenApiResponderInner for HttpApiProblem {
+impl<Ext: schemars::JsonSchema> rocket_okapi::response::OpenApiResponderInner
+    for HttpApiProblem<Ext>
+{
     fn responses(
         gen: &mut rocket_okapi::gen::OpenApiGenerator,
     ) -> rocket_okapi::Result<rocket_okapi::okapi::openapi3::Responses> {
         let mut responses = rocket_okapi::okapi::openapi3::Responses::default();
-        let schema = gen.json_schema::<HttpApiProblem>();
+        let schema = gen.json_schema::<HttpApiProblem<Ext>>();
         rocket_okapi::util::add_default_response_schema(
             &mut responses,
             PROBLEM_JSON_MEDIA_TYPE,
@@ -1012,7 +1718,27 @@ impl rocket_okapi::response::OpenApiResponderInner for HttpApiProblem {
 }
 
 #[cfg(feature = "warp")]
-impl warp::reject::Reject for HttpApiProblem {}
+impl<Ext: fmt::Debug + Send + Sync + 'static> warp::reject::Reject for HttpApiProblem<Ext> {}
+
+/// Creates a [warp] response from something that can become an
+/// `HttpApiProblem`.
+///
+/// If status is `None` `500 - Internal Server Error` is the
+/// default.
+///
+/// Requires the `warp` feature
+#[cfg(feature = "warp")]
+pub fn into_warp_response<T: Into<HttpApiProblem>>(what: T) -> warp::reply::Response {
+    let problem: HttpApiProblem = what.into();
+    problem.to_warp_response()
+}
+
+#[cfg(feature = "warp")]
+impl<Ext: Serialize + Send> warp::Reply for HttpApiProblem<Ext> {
+    fn into_response(self) -> warp::reply::Response {
+        self.to_warp_response()
+    }
+}
 
 /// Creates a [salvo::Response] from something that can become an
 /// `HttpApiProblem`.
@@ -1026,8 +1752,8 @@ pub fn into_salvo_response<T: Into<HttpApiProblem>>(what: T) -> salvo::Response
 }
 
 #[cfg(feature = "salvo")]
-impl From<HttpApiProblem> for salvo::Response {
-    fn from(problem: HttpApiProblem) -> salvo::Response {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for salvo::Response {
+    fn from(problem: HttpApiProblem<Ext>) -> salvo::Response {
         problem.to_salvo_response()
     }
 }
@@ -1044,26 +1770,111 @@ pub fn into_tide_response<T: Into<HttpApiProblem>>(what: T) -> tide::Response {
 }
 
 #[cfg(feature = "tide")]
-impl From<HttpApiProblem> for tide::Response {
-    fn from(problem: HttpApiProblem) -> tide::Response {
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for tide::Response {
+    fn from(problem: HttpApiProblem<Ext>) -> tide::Response {
         problem.to_tide_response()
     }
 }
 
-mod custom_http_status_serialization {
-    use http::StatusCode;
-    use serde::{Deserialize, Deserializer, Serializer};
-    use std::convert::TryFrom;
+/// Creates a [poem::Response] from something that can become an
+/// `HttpApiProblem`.
+///
+/// If status is `None` `500 - Internal Server Error` is the
+/// default.
+#[cfg(feature = "poem")]
+pub fn into_poem_response<T: Into<HttpApiProblem>>(what: T) -> poem::Response {
+    let problem: HttpApiProblem = what.into();
+    problem.to_poem_response()
+}
+
+#[cfg(feature = "poem")]
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for poem::Response {
+    fn from(problem: HttpApiProblem<Ext>) -> poem::Response {
+        problem.to_poem_response()
+    }
+}
 
-    pub fn serialize<S>(status: &Option<StatusCode>, s: S) -> Result<S::Ok, S::Error>
+#[cfg(feature = "poem")]
+impl<Ext: Serialize + Send> poem::IntoResponse for HttpApiProblem<Ext> {
+    fn into_response(self) -> poem::Response {
+        self.to_poem_response()
+    }
+}
+
+#[cfg(feature = "poem")]
+impl<Ext: Serialize + fmt::Debug> poem::error::ResponseError for HttpApiProblem<Ext> {
+    fn status(&self) -> StatusCode {
+        self.status_or_internal_server_error()
+    }
+
+    fn as_response(&self) -> poem::Response {
+        self.to_poem_response()
+    }
+}
+
+/// Keeps an [HttpApiProblem] wrapped by the error. Otherwise a problem
+/// for the status of the error is created. The text of the error becomes
+/// the `detail` if the status is a `4xx` status.
+#[cfg(feature = "poem")]
+impl From<poem::Error> for HttpApiProblem {
+    fn from(error: poem::Error) -> Self {
+        if let Some(problem) = error.downcast_ref::<HttpApiProblem>() {
+            return problem.clone();
+        }
+
+        let status = error.status();
+        let mut problem = HttpApiProblem::with_title_and_type(status);
+        if status.is_client_error() {
+            problem.detail = Some(error.to_string());
+        }
+        problem
+    }
+}
+
+/// The problem responded with if a problem can not be serialized
+pub(crate) fn unserializable_problem() -> HttpApiProblem {
+    HttpApiProblem::with_title_and_type(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// The extension members are serialized after the standard members. Typed
+/// members replace untyped members with the same name and extension members
+/// named like standard members are left out, so that no member is duplicated.
+impl<Ext: Serialize> Serialize for HttpApiProblem<Ext> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
-        S: Serializer,
+        S: serde::Serializer,
     {
-        if let Some(ref status_code) = *status {
-            return s.serialize_u16(status_code.as_u16());
+        use serde::ser::{Error as _, SerializeMap};
+
+        let members = self.extension_members_json().map_err(S::Error::custom)?;
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(type_url) = self.type_url.as_ref() {
+            map.serialize_entry("type", type_url)?;
         }
-        s.serialize_none()
+        if let Some(status) = self.status {
+            map.serialize_entry("status", &status.as_u16())?;
+        }
+        if let Some(title) = self.title.as_ref() {
+            map.serialize_entry("title", title)?;
+        }
+        if let Some(detail) = self.detail.as_ref() {
+            map.serialize_entry("detail", detail)?;
+        }
+        if let Some(instance) = self.instance.as_ref() {
+            map.serialize_entry("instance", instance)?;
+        }
+        for (name, value) in members.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
     }
+}
+
+mod custom_http_status_serialization {
+    use http::StatusCode;
+    use serde::{Deserialize, Deserializer};
+    use std::convert::TryFrom;
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<StatusCode>, D::Error>
     where
@@ -1086,3 +1897,7 @@ mod custom_http_status_serialization {
 
 #[cfg(test)]
 mod test;
+
+// The derived code refers to `::http_api_problem`
+#[cfg(all(test, feature = "api-error"))]
+extern crate self as http_api_problem;
@@ -0,0 +1,625 @@
+//! Content negotiation for problem responses.
+//!
+//! A [Negotiator] selects a [ProblemFormat] based on the `Accept` header
+//! of a request and renders an [HttpApiProblem] accordingly.
+//!
+//! Responses created by a [Negotiator] always contain the header `Vary: Accept`.
+//! If none of the enabled formats is acceptable to the client the configured default
+//! format is used.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! let negotiator = Negotiator::new(ProblemFormat::ProblemJson);
+//! let problem = HttpApiProblem::with_title(StatusCode::NOT_FOUND);
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert(http::header::ACCEPT, "text/html,*/*;q=0.8".parse().unwrap());
+//!
+//! let response = negotiator.to_http_response(&problem, &headers);
+//!
+//! assert_eq!(StatusCode::NOT_FOUND, response.status());
+//! assert_eq!("text/html; charset=utf-8", response.headers()["content-type"]);
+//! assert_eq!("Accept", response.headers()["vary"]);
+//! ```
+use std::fmt::Write;
+
+use http::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, VARY};
+
+use super::*;
+
+/// The formats a problem can be rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProblemFormat {
+    /// `application/problem+json`
+    ProblemJson,
+    /// `application/json`
+    Json,
+    /// `application/problem+xml`
+    ///
+    /// Requires the `xml` feature
+    #[cfg(feature = "xml")]
+    ProblemXml,
+    /// `text/html`
+    Html,
+    /// `text/plain`
+    Text,
+}
+
+impl ProblemFormat {
+    /// All formats which are available with the enabled features
+    pub fn all() -> Vec<ProblemFormat> {
+        vec![
+            ProblemFormat::ProblemJson,
+            ProblemFormat::Json,
+            #[cfg(feature = "xml")]
+            ProblemFormat::ProblemXml,
+            ProblemFormat::Html,
+            ProblemFormat::Text,
+        ]
+    }
+
+    /// The media type of the format without any parameters
+    pub fn media_type(self) -> &'static str {
+        match self {
+            ProblemFormat::ProblemJson => PROBLEM_JSON_MEDIA_TYPE,
+            ProblemFormat::Json => "application/json",
+            #[cfg(feature = "xml")]
+            ProblemFormat::ProblemXml => PROBLEM_XML_MEDIA_TYPE,
+            ProblemFormat::Html => "text/html",
+            ProblemFormat::Text => "text/plain",
+        }
+    }
+
+    /// The value to be used for the `Content-Type` header
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ProblemFormat::Html => "text/html; charset=utf-8",
+            ProblemFormat::Text => "text/plain; charset=utf-8",
+            other => other.media_type(),
+        }
+    }
+}
+
+/// Selects the [ProblemFormat] of a response based on the `Accept` header
+/// of the request.
+#[derive(Debug, Clone)]
+pub struct Negotiator {
+    default: ProblemFormat,
+    formats: Vec<ProblemFormat>,
+}
+
+impl Negotiator {
+    /// Creates a new [Negotiator] with all formats enabled.
+    ///
+    /// The given format is used if there is no `Accept` header
+    /// or none of the enabled formats is acceptable.
+    pub fn new(default: ProblemFormat) -> Self {
+        Self {
+            default,
+            formats: ProblemFormat::all(),
+        }
+    }
+
+    /// Restricts the formats which may be selected.
+    ///
+    /// The order is the order of preference if the client accepts
+    /// multiple formats with the same quality. The default format
+    /// is always preferred.
+    pub fn formats<I: IntoIterator<Item = ProblemFormat>>(mut self, formats: I) -> Self {
+        self.formats = formats.into_iter().collect();
+        self
+    }
+
+    /// The format used if no other format can be negotiated
+    pub fn default_format(&self) -> ProblemFormat {
+        self.default
+    }
+
+    /// Selects the format for the value of an `Accept` header.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let negotiator = Negotiator::new(ProblemFormat::ProblemJson);
+    ///
+    /// assert_eq!(ProblemFormat::ProblemJson, negotiator.negotiate(None));
+    /// assert_eq!(ProblemFormat::ProblemJson, negotiator.negotiate(Some("*/*")));
+    /// assert_eq!(ProblemFormat::Json, negotiator.negotiate(Some("application/json")));
+    /// assert_eq!(ProblemFormat::Text, negotiator.negotiate(Some("text/html;q=0.5, text/plain")));
+    /// assert_eq!(ProblemFormat::ProblemJson, negotiator.negotiate(Some("image/png")));
+    /// ```
+    pub fn negotiate(&self, accept: Option<&str>) -> ProblemFormat {
+        let accept = match accept {
+            Some(accept) if !accept.trim().is_empty() => accept,
+            _ => return self.default,
+        };
+
+        let ranges = parse_accept(accept);
+
+        let candidates = std::iter::once(self.default)
+            .filter(|f| self.formats.contains(f))
+            .chain(self.formats.iter().copied().filter(|f| *f != self.default));
+
+        let mut best: Option<(ProblemFormat, u16)> = None;
+        for format in candidates {
+            let quality = quality_of(format.media_type(), &ranges);
+            if quality == 0 {
+                continue;
+            }
+            match best {
+                Some((_, best_quality)) if best_quality >= quality => {}
+                _ => best = Some((format, quality)),
+            }
+        }
+
+        best.map(|(format, _)| format).unwrap_or(self.default)
+    }
+
+    /// Selects the format for the `Accept` header contained in the given headers.
+    pub fn negotiate_headers(&self, headers: &HeaderMap) -> ProblemFormat {
+        self.negotiate(accept_header(headers))
+    }
+
+    /// Renders the problem in the format negotiated for the value of an `Accept` header.
+    ///
+    /// A problem which can not be serialized is rendered as a
+    /// `500 - Internal Server Error` problem.
+    pub fn render<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        accept: Option<&str>,
+    ) -> NegotiatedProblem {
+        let format = self.negotiate(accept);
+        match problem.try_render(format) {
+            Ok(body) => NegotiatedProblem {
+                status: problem.status_or_internal_server_error(),
+                format,
+                body,
+            },
+            Err(_) => NegotiatedProblem {
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+                format,
+                body: unserializable_problem().render(format),
+            },
+        }
+    }
+
+    /// Creates an [http::Response] for the `Accept` header contained in the given headers.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    pub fn to_http_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        headers: &HeaderMap,
+    ) -> http::Response<String> {
+        self.render(problem, accept_header(headers))
+            .into_http_response()
+    }
+
+    /// Creates a [hyper] response for the `Accept` header contained in the given headers.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `hyper` feature
+    #[cfg(feature = "hyper")]
+    pub fn to_hyper_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        headers: &hyper::HeaderMap,
+    ) -> hyper::Response<String> {
+        self.to_http_response(problem, headers)
+    }
+
+    /// Creates an axum [Response](axum_core::response::Response) for the `Accept`
+    /// header contained in the given headers.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `axum` feature
+    #[cfg(feature = "axum")]
+    pub fn to_axum_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        headers: &HeaderMap,
+    ) -> axum_core::response::Response {
+        self.to_http_response(problem, headers)
+            .map(axum_core::body::Body::from)
+    }
+
+    /// Creates an `actix` response for the `Accept` header of the given request.
+    ///
+    /// If status is `None` or not convertible
+    /// to an actix status `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `actix-web` feature
+    #[cfg(feature = "actix-web")]
+    pub fn to_actix_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        request: &actix_web::HttpRequest,
+    ) -> actix_web::HttpResponse {
+        let accept = request
+            .headers()
+            .get(actix_web::http::header::ACCEPT)
+            .and_then(|v| v.to_str().ok());
+        let negotiated = self.render(problem, accept);
+
+        let actix_status = actix_web::http::StatusCode::from_u16(negotiated.status.as_u16())
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        actix_web::HttpResponse::build(actix_status)
+            .append_header((
+                actix_web::http::header::CONTENT_TYPE,
+                negotiated.content_type(),
+            ))
+            .append_header((actix_web::http::header::VARY, "Accept"))
+            .body(negotiated.body)
+    }
+
+    /// Creates a `rocket` response for the `Accept` header of the given request.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `rocket` feature
+    #[cfg(feature = "rocket")]
+    pub fn to_rocket_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        request: &rocket::Request<'_>,
+    ) -> rocket::Response<'static> {
+        use rocket::http::{ContentType, Header, Status};
+        use rocket::Response;
+        use std::io::Cursor;
+
+        let negotiated = self.render(problem, request.headers().get_one("Accept"));
+        let content_type = match negotiated.format {
+            ProblemFormat::ProblemJson => ContentType::new("application", "problem+json"),
+            ProblemFormat::Json => ContentType::JSON,
+            #[cfg(feature = "xml")]
+            ProblemFormat::ProblemXml => ContentType::new("application", "problem+xml"),
+            ProblemFormat::Html => ContentType::HTML,
+            ProblemFormat::Text => ContentType::Plain,
+        };
+
+        Response::build()
+            .status(Status {
+                code: negotiated.status.as_u16(),
+            })
+            .header(content_type)
+            .header(Header::new("Vary", "Accept"))
+            .sized_body(negotiated.body.len(), Cursor::new(negotiated.body))
+            .finalize()
+    }
+
+    /// Creates a [salvo] response for the `Accept` header of the given request.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `salvo` feature
+    #[cfg(feature = "salvo")]
+    pub fn to_salvo_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        request: &salvo::Request,
+    ) -> salvo::Response {
+        let accept = request
+            .headers()
+            .get(salvo::http::header::ACCEPT)
+            .and_then(|v| v.to_str().ok());
+        let negotiated = self.render(problem, accept);
+
+        let (mut parts, body) = salvo::hyper::Response::new(negotiated.body).into_parts();
+        parts.status = negotiated.status;
+        parts.headers.insert(
+            salvo::http::header::CONTENT_TYPE,
+            salvo::http::HeaderValue::from_static(negotiated.format.content_type()),
+        );
+        parts.headers.insert(
+            salvo::http::header::VARY,
+            salvo::http::HeaderValue::from_static("Accept"),
+        );
+
+        salvo::hyper::Response::from_parts(parts, body).into()
+    }
+
+    /// Creates a [tide] response for the `Accept` header of the given request.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `tide` feature
+    #[cfg(feature = "tide")]
+    pub fn to_tide_response<Ext: Serialize, State>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        request: &tide::Request<State>,
+    ) -> tide::Response {
+        let accept = request.header("Accept").map(|v| v.as_str());
+        let negotiated = self.render(problem, accept);
+
+        tide::Response::builder(negotiated.status.as_u16())
+            .body(negotiated.body)
+            .header("Vary", "Accept")
+            .content_type(negotiated.format.content_type())
+            .build()
+    }
+
+    /// Creates a [poem] response for the `Accept` header of the given request.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default.
+    ///
+    /// Requires the `poem` feature
+    #[cfg(feature = "poem")]
+    pub fn to_poem_response<Ext: Serialize>(
+        &self,
+        problem: &HttpApiProblem<Ext>,
+        request: &poem::Request,
+    ) -> poem::Response {
+        let negotiated = self.render(problem, request.header(http::header::ACCEPT));
+
+        poem::Response::builder()
+            .status(negotiated.status)
+            .header(http::header::VARY, "Accept")
+            .content_type(negotiated.format.content_type())
+            .body(negotiated.body)
+    }
+}
+
+impl Default for Negotiator {
+    fn default() -> Self {
+        Self::new(ProblemFormat::ProblemJson)
+    }
+}
+
+/// A problem rendered in a negotiated [ProblemFormat]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegotiatedProblem {
+    /// The status of the response
+    pub status: StatusCode,
+    /// The selected format
+    pub format: ProblemFormat,
+    /// The rendered problem
+    pub body: String,
+}
+
+impl NegotiatedProblem {
+    /// The value to be used for the `Content-Type` header
+    pub fn content_type(&self) -> &'static str {
+        self.format.content_type()
+    }
+
+    /// Creates an [http::Response] with the headers `Content-Type`,
+    /// `Content-Length` and `Vary` set.
+    pub fn into_http_response(self) -> http::Response<String> {
+        let length = self.body.len();
+        let mut response = http::Response::new(self.body);
+
+        *response.status_mut() = self.status;
+        let headers = response.headers_mut();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(self.format.content_type()),
+        );
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(length));
+        headers.insert(VARY, HeaderValue::from_static("Accept"));
+
+        response
+    }
+}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Renders the problem in the given [ProblemFormat]
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::with_title(StatusCode::NOT_FOUND).detail("No such account");
+    ///
+    /// assert_eq!(
+    ///     "404 Not Found\ntitle: Not Found\ndetail: No such account\n",
+    ///     p.render(ProblemFormat::Text)
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the typed extension members do not serialize to a map.
+    /// Use [HttpApiProblem::try_render] to handle this case.
+    pub fn render(&self, format: ProblemFormat) -> String {
+        self.try_render(format).unwrap()
+    }
+
+    /// Renders the problem in the given [ProblemFormat]
+    ///
+    /// Fails if the typed extension members do not serialize to a map.
+    pub fn try_render(&self, format: ProblemFormat) -> Result<String, serde_json::Error> {
+        match format {
+            ProblemFormat::ProblemJson | ProblemFormat::Json => self.try_json_string(),
+            #[cfg(feature = "xml")]
+            ProblemFormat::ProblemXml => self.try_xml_string(),
+            ProblemFormat::Html => self.html_string(),
+            ProblemFormat::Text => self.text_string(),
+        }
+    }
+
+    fn text_string(&self) -> Result<String, serde_json::Error> {
+        let mut text = String::new();
+
+        match self.status {
+            Some(status) => {
+                let _ = writeln!(text, "{}", status);
+            }
+            None => text.push_str("<no status>\n"),
+        }
+        for (name, value) in self.standard_members() {
+            let _ = writeln!(text, "{}: {}", name, value);
+        }
+        for (name, value) in self.sorted_extension_members()? {
+            let _ = writeln!(text, "{}: {}", name, value);
+        }
+
+        Ok(text)
+    }
+
+    fn html_string(&self) -> Result<String, serde_json::Error> {
+        let status = self.status_or_internal_server_error();
+        let heading = self
+            .title
+            .as_deref()
+            .or_else(|| status.canonical_reason())
+            .unwrap_or("Error");
+
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(
+            html,
+            "<title>{} {}</title>\n</head>\n<body>\n<h1>{}</h1>",
+            status.as_u16(),
+            escape_html(heading),
+            escape_html(heading)
+        );
+        if let Some(detail) = self.detail.as_deref() {
+            let _ = writeln!(html, "<p>{}</p>", escape_html(detail));
+        }
+        html.push_str("<dl>\n");
+        let _ = writeln!(html, "<dt>status</dt><dd>{}</dd>", status.as_u16());
+        if let Some(type_url) = self.type_url.as_deref() {
+            if uri::is_linkable(type_url) {
+                let _ = writeln!(
+                    html,
+                    "<dt>type</dt><dd><a href=\"{0}\">{0}</a></dd>",
+                    escape_html(type_url)
+                );
+            } else {
+                let _ = writeln!(html, "<dt>type</dt><dd>{}</dd>", escape_html(type_url));
+            }
+        }
+        if let Some(instance) = self.instance.as_deref() {
+            let _ = writeln!(html, "<dt>instance</dt><dd>{}</dd>", escape_html(instance));
+        }
+        for (name, value) in self.sorted_extension_members()? {
+            let _ = writeln!(
+                html,
+                "<dt>{}</dt><dd><code>{}</code></dd>",
+                escape_html(&name),
+                escape_html(&value.to_string())
+            );
+        }
+        html.push_str("</dl>\n</body>\n</html>\n");
+
+        Ok(html)
+    }
+
+    fn standard_members(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("title", self.title.as_deref()),
+            ("detail", self.detail.as_deref()),
+            ("type", self.type_url.as_deref()),
+            ("instance", self.instance.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| (name, v)))
+    }
+
+    fn sorted_extension_members(&self) -> Result<Vec<(String, Value)>, serde_json::Error> {
+        let mut members: Vec<_> = self.extension_members_json()?.into_iter().collect();
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(members)
+    }
+}
+
+fn accept_header(headers: &HeaderMap) -> Option<&str> {
+    headers.get(ACCEPT).and_then(|v| v.to_str().ok())
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A media range of an `Accept` header with its quality in thousandths
+struct MediaRange<'a> {
+    type_: &'a str,
+    subtype: &'a str,
+    quality: u16,
+}
+
+fn parse_accept(accept: &str) -> Vec<MediaRange<'_>> {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let (type_, subtype) = parts.next()?.trim().split_once('/')?;
+            let mut quality = 1000;
+            for param in parts {
+                if let Some((name, value)) = param.split_once('=') {
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        quality = parse_quality(value.trim())?;
+                    }
+                }
+            }
+            Some(MediaRange {
+                type_: type_.trim(),
+                subtype: subtype.trim(),
+                quality,
+            })
+        })
+        .collect()
+}
+
+fn parse_quality(value: &str) -> Option<u16> {
+    let q: f32 = value.parse().ok()?;
+    if (0.0..=1.0).contains(&q) {
+        Some((q * 1000.0).round() as u16)
+    } else {
+        None
+    }
+}
+
+/// The quality of the most specific matching media range or 0
+fn quality_of(media_type: &str, ranges: &[MediaRange]) -> u16 {
+    let (type_, subtype) = media_type.split_once('/').unwrap_or((media_type, ""));
+
+    ranges
+        .iter()
+        .filter_map(|range| {
+            let specificity = if range.type_ == "*" && range.subtype == "*" {
+                0
+            } else if range.type_.eq_ignore_ascii_case(type_) && range.subtype == "*" {
+                1
+            } else if range.type_.eq_ignore_ascii_case(type_)
+                && range.subtype.eq_ignore_ascii_case(subtype)
+            {
+                2
+            } else {
+                return None;
+            };
+            Some((specificity, range.quality))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, quality)| quality)
+        .unwrap_or(0)
+}
@@ -0,0 +1,121 @@
+//! Problems for the errors of ntex's extractors
+//!
+//! The errors of `Json`, `Query`, `Path` and `Form` and ntex's `web::Error`
+//! can be converted into an [HttpApiProblem] (and an `ApiError` with the
+//! feature `api-error`). The status of the error is kept and its text becomes
+//! the `detail` if the status is a `4xx` status. A field named by the error (e.g. "missing field `name`")
+//! is added as an [InvalidParam].
+//!
+//! ntex passes the error of an extractor to the handler if the
+//! extractor is wrapped in a `Result`:
+//!
+//! ```rust
+//! use http_api_problem::*;
+//! use ntex::web::error::JsonPayloadError;
+//! use ntex::web::{self, App, HttpResponse};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! async fn create_user(
+//!     user: Result<web::types::Json<User>, JsonPayloadError>,
+//! ) -> Result<HttpResponse, HttpApiProblem> {
+//!     let user = user.map_err(HttpApiProblem::from)?;
+//!     println!("created {}", user.name);
+//!     Ok(HttpResponse::Created().finish())
+//! }
+//!
+//! let app = App::new().route("/users", web::post().to(create_user));
+//! ```
+use ntex::web::error::{
+    JsonPayloadError, PathError, PayloadError, QueryPayloadError, UrlencodedError,
+};
+use ntex::web::{DefaultError, WebResponseError};
+
+use super::*;
+
+fn error_problem<E: WebResponseError<DefaultError> + Error>(err: &E) -> ValidationProblem {
+    let status = err.status_code();
+    let mut problem = ValidationProblem::new().status(status);
+    if status.is_client_error() {
+        problem = problem.detail(err.to_string());
+    }
+
+    let param = match err.source() {
+        Some(source) => InvalidParam::from_serde_message(&source.to_string()),
+        None => None,
+    };
+
+    match param {
+        Some(param) => problem.param(param),
+        None => problem,
+    }
+}
+
+macro_rules! impl_error_conversions {
+    ($($error:ty),*) => {
+        $(
+            impl From<&$error> for ValidationProblem {
+                fn from(err: &$error) -> Self {
+                    error_problem(err)
+                }
+            }
+
+            impl From<$error> for ValidationProblem {
+                fn from(err: $error) -> Self {
+                    error_problem(&err)
+                }
+            }
+
+            impl From<$error> for HttpApiProblem {
+                fn from(err: $error) -> Self {
+                    error_problem(&err).into_http_api_problem()
+                }
+            }
+
+            #[cfg(feature = "api-error")]
+            impl IntoApiError for $error {
+                fn into_api_error(self) -> ApiError {
+                    error_problem(&self).into_api_error()
+                }
+            }
+        )*
+    };
+}
+
+impl_error_conversions!(
+    JsonPayloadError,
+    QueryPayloadError,
+    PathError,
+    UrlencodedError,
+    PayloadError
+);
+
+/// The text of the error becomes the `detail` if the status is a `4xx` status.
+impl From<ntex::web::Error> for HttpApiProblem {
+    fn from(err: ntex::web::Error) -> Self {
+        let status = err.as_response_error().status_code();
+        let mut problem = HttpApiProblem::with_title_and_type(status);
+        if status.is_client_error() {
+            problem.detail = Some(err.to_string());
+        }
+        problem
+    }
+}
+
+/// The text of the error becomes the message if the status is a `4xx` status.
+#[cfg(feature = "api-error")]
+impl IntoApiError for ntex::web::Error {
+    fn into_api_error(self) -> ApiError {
+        let status = self.as_response_error().status_code();
+        let builder = ApiError::builder(status);
+        if status.is_client_error() {
+            builder.message(self).finish()
+        } else {
+            builder.finish()
+        }
+    }
+}
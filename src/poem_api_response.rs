@@ -0,0 +1,129 @@
+//! [ApiResponse] for [HttpApiProblem] and `ApiError`
+//!
+//! Both are documented as the `default` response of an operation with
+//! an `application/problem+json` body described by the `HttpApiProblem`
+//! schema. If an operation responds with just a problem, errors of
+//! parsing the request are answered with a problem, too.
+//!
+//! #Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//! use poem_openapi::param::Path;
+//! use poem_openapi::payload::Json;
+//! use poem_openapi::{Object, OpenApi};
+//!
+//! #[derive(Object)]
+//! struct User {
+//!     id: u32,
+//! }
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/users/:id", method = "get")]
+//!     async fn get_user(&self, id: Path<u32>) -> Result<Json<User>, HttpApiProblem> {
+//!         match id.0 {
+//!             0 => Err(HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)),
+//!             id => Ok(Json(User { id })),
+//!         }
+//!     }
+//! }
+//! ```
+use poem_openapi::registry::{
+    MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
+};
+use poem_openapi::ApiResponse;
+
+use super::*;
+
+use super::schema_descriptions::SCHEMA_NAME;
+
+impl ApiResponse for HttpApiProblem {
+    const BAD_REQUEST_HANDLER: bool = true;
+
+    fn meta() -> MetaResponses {
+        problem_responses()
+    }
+
+    fn register(registry: &mut Registry) {
+        register_problem_schema(registry);
+    }
+
+    fn from_parse_request_error(err: poem::Error) -> Self {
+        err.into()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl ApiResponse for ApiError {
+    const BAD_REQUEST_HANDLER: bool = true;
+
+    fn meta() -> MetaResponses {
+        problem_responses()
+    }
+
+    fn register(registry: &mut Registry) {
+        register_problem_schema(registry);
+    }
+
+    fn from_parse_request_error(err: poem::Error) -> Self {
+        err.into()
+    }
+}
+
+fn problem_responses() -> MetaResponses {
+    MetaResponses {
+        responses: vec![MetaResponse {
+            description: schema_descriptions::RESPONSE,
+            status: None,
+            status_range: None,
+            content: vec![MetaMediaType {
+                content_type: PROBLEM_JSON_MEDIA_TYPE,
+                schema: MetaSchemaRef::Reference(SCHEMA_NAME.to_string()),
+            }],
+            headers: vec![],
+        }],
+    }
+}
+
+fn register_problem_schema(registry: &mut Registry) {
+    registry.create_schema::<HttpApiProblem, _>(SCHEMA_NAME.to_string(), |_| MetaSchema {
+        description: Some(schema_descriptions::PROBLEM),
+        properties: vec![
+            (
+                "type",
+                property(MetaSchema::new("string"), schema_descriptions::TYPE),
+            ),
+            (
+                "status",
+                property(
+                    MetaSchema::new_with_format("integer", "uint16"),
+                    schema_descriptions::STATUS,
+                ),
+            ),
+            (
+                "title",
+                property(MetaSchema::new("string"), schema_descriptions::TITLE),
+            ),
+            (
+                "detail",
+                property(MetaSchema::new("string"), schema_descriptions::DETAIL),
+            ),
+            (
+                "instance",
+                property(MetaSchema::new("string"), schema_descriptions::INSTANCE),
+            ),
+        ],
+        additional_properties: Some(Box::new(MetaSchemaRef::Inline(Box::new(MetaSchema::ANY)))),
+        ..MetaSchema::new("object")
+    });
+}
+
+fn property(schema: MetaSchema, description: &'static str) -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        description: Some(description),
+        ..schema
+    }))
+}
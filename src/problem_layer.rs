@@ -0,0 +1,291 @@
+//! A [tower](https://docs.rs/tower) [Layer] which turns error responses
+//! into problems.
+//!
+//! Frameworks and middlewares create error responses of their own, e.g.
+//! axum for unmatched routes (`404`) and methods (`405`) or `tower-http`
+//! for timeouts (`408`) and bodies exceeding a limit (`413`). These have
+//! an empty or plain text body.
+//!
+//! The [ProblemLayer] replaces the body of every response with a status of
+//! `4xx` or `5xx` which is not already a problem document with an
+//! [HttpApiProblem] created by [HttpApiProblem::with_title_and_type].
+//! All headers except `Content-Type`, `Content-Length` and `Content-Encoding`
+//! are kept.
+//! Responses to `HEAD` requests only get the `Content-Type` of a problem
+//! and no body.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "axum-extract")]
+//! # {
+//! # use axum_crate as axum;
+//! use axum::{routing::get, Router};
+//! use http_api_problem::ProblemLayer;
+//!
+//! async fn handler() -> &'static str {
+//!     "Hello"
+//! }
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(handler))
+//!     .layer(ProblemLayer::new().detail_from_body(true));
+//! # }
+//! ```
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use http::header::{self, HeaderValue};
+use http::response::Parts;
+use http::{Method, Request, Response};
+use http_body::Body;
+use http_body_util::combinators::Collect;
+use http_body_util::{BodyExt, Either, Full};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::*;
+
+/// The maximum length of a body to be used as the `detail` of a problem
+const MAX_DETAIL_LEN: u64 = 4096;
+
+/// The body of a response passed through a [ProblemService]
+///
+/// It is either the original body or the body of a problem.
+pub type ProblemBody<B> = Either<B, Full<Bytes>>;
+
+/// A [Layer] which replaces the body of error responses with a problem.
+///
+/// Requires the `tower` feature
+#[derive(Debug, Clone, Default)]
+pub struct ProblemLayer {
+    detail_from_body: bool,
+}
+
+impl ProblemLayer {
+    /// Creates a new layer which replaces the body of error responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the original body as the `detail` of the problem.
+    ///
+    /// Only bodies of responses with a `4xx` status of type `text/plain`
+    /// (or without a `Content-Type`) of at most 4096 bytes are used.
+    /// This is disabled by default.
+    pub fn detail_from_body(mut self, enabled: bool) -> Self {
+        self.detail_from_body = enabled;
+        self
+    }
+}
+
+impl<S> Layer<S> for ProblemLayer {
+    type Service = ProblemService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ProblemService {
+            inner,
+            detail_from_body: self.detail_from_body,
+        }
+    }
+}
+
+/// A [Service] which replaces the body of error responses with a problem.
+///
+/// Created by [ProblemLayer].
+///
+/// Requires the `tower` feature
+#[derive(Debug, Clone)]
+pub struct ProblemService<S> {
+    inner: S,
+    detail_from_body: bool,
+}
+
+impl<S> ProblemService<S> {
+    /// Creates a new service which replaces the body of error responses
+    pub fn new(inner: S) -> Self {
+        ProblemLayer::new().layer(inner)
+    }
+}
+
+impl<S, ReqB, B> Service<Request<ReqB>> for ProblemService<S>
+where
+    S: Service<Request<ReqB>, Response = Response<B>>,
+    B: Body<Data = Bytes>,
+{
+    type Response = Response<ProblemBody<B>>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqB>) -> Self::Future {
+        let is_head = req.method() == Method::HEAD;
+        ResponseFuture {
+            state: State::Inner {
+                future: self.inner.call(req),
+            },
+            detail_from_body: self.detail_from_body,
+            is_head,
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of a [ProblemService]
+    pub struct ResponseFuture<F, B>
+    where
+        B: Body,
+    {
+        #[pin]
+        state: State<F, B>,
+        detail_from_body: bool,
+        is_head: bool,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F, B>
+    where
+        B: Body,
+    {
+        Inner {
+            #[pin]
+            future: F,
+        },
+        Collecting {
+            #[pin]
+            collect: Collect<B>,
+            parts: Option<Parts>,
+        },
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body<Data = Bytes>,
+{
+    type Output = Result<Response<ProblemBody<B>>, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let this = self.as_mut().project();
+            let collect = match this.state.project() {
+                StateProj::Inner { future } => {
+                    let response = ready!(future.poll(cx))?;
+
+                    if !is_non_problem_error(&response) {
+                        return Poll::Ready(Ok(response.map(Either::Left)));
+                    }
+
+                    let (parts, body) = response.into_parts();
+                    if *this.is_head {
+                        return Poll::Ready(Ok(head_response(parts)));
+                    }
+
+                    if *this.detail_from_body
+                        && parts.status.is_client_error()
+                        && is_short_text(&parts, &body)
+                    {
+                        State::Collecting {
+                            collect: body.collect(),
+                            parts: Some(parts),
+                        }
+                    } else {
+                        return Poll::Ready(Ok(problem_response(parts, None)));
+                    }
+                }
+                StateProj::Collecting { collect, parts } => {
+                    let detail = ready!(collect.poll(cx))
+                        .ok()
+                        .and_then(|collected| String::from_utf8(collected.to_bytes().into()).ok())
+                        .map(|detail| detail.trim().to_string())
+                        .filter(|detail| !detail.is_empty());
+                    let parts = parts.take().expect("polled after completion");
+
+                    return Poll::Ready(Ok(problem_response(parts, detail)));
+                }
+            };
+
+            self.as_mut().project().state.set(collect);
+        }
+    }
+}
+
+fn is_non_problem_error<B>(response: &Response<B>) -> bool {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return false;
+    }
+
+    !response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| {
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("application/problem+")
+        })
+        .unwrap_or(false)
+}
+
+fn is_short_text<B: Body>(parts: &Parts, body: &B) -> bool {
+    let is_text = match parts.headers.get(header::CONTENT_TYPE) {
+        Some(content_type) => content_type
+            .to_str()
+            .map(|content_type| {
+                content_type
+                    .trim_start()
+                    .to_ascii_lowercase()
+                    .starts_with("text/plain")
+            })
+            .unwrap_or(false),
+        None => true,
+    };
+
+    is_text
+        && !body.is_end_stream()
+        && body
+            .size_hint()
+            .upper()
+            .map(|len| len <= MAX_DETAIL_LEN)
+            .unwrap_or(false)
+}
+
+fn problem_response<B>(mut parts: Parts, detail: Option<String>) -> Response<ProblemBody<B>> {
+    let mut problem = HttpApiProblem::with_title_and_type(parts.status);
+    problem.detail = detail;
+    let body = problem.json_bytes();
+
+    set_problem_headers(&mut parts);
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+    Response::from_parts(parts, Either::Right(Full::new(Bytes::from(body))))
+}
+
+/// The response to a `HEAD` request has the headers of a problem but no body
+fn head_response<B>(mut parts: Parts) -> Response<ProblemBody<B>> {
+    set_problem_headers(&mut parts);
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    Response::from_parts(parts, Either::Right(Full::new(Bytes::new())))
+}
+
+fn set_problem_headers(parts: &mut Parts) {
+    parts.headers.remove(header::CONTENT_ENCODING);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PROBLEM_JSON_MEDIA_TYPE),
+    );
+}
@@ -0,0 +1,583 @@
+//! Definitions of the problem types of a service
+//!
+//! A [ProblemType] defines a `type` URL together with the fixed `title`
+//! and the default status of all problems of that type. RFC9457 states
+//! that the title of a problem type should not change from occurrence to
+//! occurrence, so problems should be created from their [ProblemType]
+//! instead of repeating the title and `type` URL in every handler.
+//!
+//! A [ProblemTypeRegistry] holds all problem types of a service. It makes sure
+//! that neither names nor `type` URLs are registered with different definitions
+//! and can check that a problem matches the registered definition of its type.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+//!     "out-of-credit",
+//!     "https://example.com/probs/out-of-credit",
+//!     "You do not have enough credit.",
+//!     StatusCode::FORBIDDEN,
+//! )
+//! .with_description("The balance of the account is lower than the price of the purchase.");
+//!
+//! let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+//!
+//! let problem = registry
+//!     .problem("out-of-credit")
+//!     .unwrap()
+//!     .detail("Your current balance is 30, but that costs 50.");
+//!
+//! assert_eq!(Some(StatusCode::FORBIDDEN), problem.status);
+//! assert_eq!(Some("You do not have enough credit."), problem.title.as_deref());
+//! assert!(registry.check(&problem).is_ok());
+//! ```
+use serde::ser::{SerializeStruct, Serializer};
+
+use super::*;
+
+/// The definition of a problem type
+///
+/// All fields are `&'static str`s so that problem types can be defined as
+/// constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProblemType {
+    name: &'static str,
+    type_url: &'static str,
+    title: &'static str,
+    status: StatusCode,
+    description: Option<&'static str>,
+    documentation_url: Option<&'static str>,
+    extension_members: &'static [ExtensionMember],
+}
+
+impl ProblemType {
+    /// Creates a new problem type.
+    ///
+    /// The `name` identifies the problem type within a [ProblemTypeRegistry].
+    /// The `status` is the default status of problems of this type.
+    pub const fn new(
+        name: &'static str,
+        type_url: &'static str,
+        title: &'static str,
+        status: StatusCode,
+    ) -> Self {
+        Self {
+            name,
+            type_url,
+            title,
+            status,
+            description: None,
+            documentation_url: None,
+            extension_members: &[],
+        }
+    }
+
+    /// Sets a description of the problem type for its documentation.
+    pub const fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// Sets a link to the documentation of the problem type.
+    ///
+    /// This is only needed if the `type` URL does not dereference
+    /// to the documentation.
+    pub const fn with_documentation_url(mut self, documentation_url: &'static str) -> Self {
+        self.documentation_url = Some(documentation_url);
+        self
+    }
+
+    /// Sets the extension members of problems of this type for
+    /// its documentation.
+    pub const fn with_extension_members(
+        mut self,
+        extension_members: &'static [ExtensionMember],
+    ) -> Self {
+        self.extension_members = extension_members;
+        self
+    }
+
+    /// The name of the problem type within a [ProblemTypeRegistry]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The `type` URL of problems of this type
+    pub const fn type_url(&self) -> &'static str {
+        self.type_url
+    }
+
+    /// The `title` of problems of this type
+    pub const fn title(&self) -> &'static str {
+        self.title
+    }
+
+    /// The default status of problems of this type
+    pub const fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The description of the problem type
+    pub const fn description(&self) -> Option<&'static str> {
+        self.description
+    }
+
+    /// The link to the documentation of the problem type
+    ///
+    /// If none was set this is the `type` URL.
+    pub fn documentation_url(&self) -> &'static str {
+        self.documentation_url.unwrap_or(self.type_url)
+    }
+
+    /// The documented extension members of problems of this type
+    pub const fn extension_members(&self) -> &'static [ExtensionMember] {
+        self.extension_members
+    }
+
+    /// Creates an [HttpApiProblem] of this type with the default status.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// const NOT_PAID: ProblemType = ProblemType::new(
+    ///     "not-paid",
+    ///     "/probs/not-paid",
+    ///     "The order has not been paid.",
+    ///     StatusCode::CONFLICT,
+    /// );
+    ///
+    /// let problem = NOT_PAID.problem();
+    /// assert_eq!(Some(StatusCode::CONFLICT), problem.status);
+    /// assert_eq!(Some("/probs/not-paid"), problem.type_url.as_deref());
+    /// assert_eq!(Some("The order has not been paid."), problem.title.as_deref());
+    /// ```
+    pub fn problem(&self) -> HttpApiProblem {
+        HttpApiProblem::new(self.status)
+            .type_url(self.type_url)
+            .title(self.title)
+    }
+
+    /// Creates an [ApiErrorBuilder] of this type with the default status.
+    ///
+    /// Requires the `api-error` feature
+    #[cfg(feature = "api-error")]
+    pub fn api_error_builder(&self) -> ApiErrorBuilder {
+        ApiError::builder(self.status)
+            .type_url(self.type_url)
+            .title(self.title)
+    }
+
+    /// Creates an [ApiError] of this type with the default status.
+    ///
+    /// Requires the `api-error` feature
+    #[cfg(feature = "api-error")]
+    pub fn api_error(&self) -> ApiError {
+        self.api_error_builder().finish()
+    }
+
+    /// Creates an example problem of this type for its documentation.
+    ///
+    /// The example contains the examples of the extension members.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+    ///     "out-of-credit",
+    ///     "/probs/out-of-credit",
+    ///     "You do not have enough credit.",
+    ///     StatusCode::FORBIDDEN,
+    /// )
+    /// .with_extension_members(&[
+    ///     ExtensionMember::new("balance", "integer", "The current balance").with_example("30"),
+    ///     ExtensionMember::new("accounts", "array", "The accounts of the user"),
+    /// ]);
+    ///
+    /// let example = OUT_OF_CREDIT.example();
+    /// assert_eq!(Some(&serde_json::json!(30)), example.json_value("balance"));
+    /// assert_eq!(None, example.json_value("accounts"));
+    /// ```
+    pub fn example(&self) -> HttpApiProblem {
+        let mut problem = self.problem();
+        for member in self.extension_members {
+            if let Some(example) = member.example_value() {
+                problem.set_value(member.name, &example);
+            }
+        }
+        problem
+    }
+
+    /// Returns `true` if the `type` URL of the problem is the `type` URL
+    /// of this problem type.
+    pub fn is_type_of<Ext>(&self, problem: &HttpApiProblem<Ext>) -> bool {
+        problem.type_url.as_deref() == Some(self.type_url)
+    }
+}
+
+/// The documentation of an extension member of a [ProblemType]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionMember {
+    name: &'static str,
+    schema_type: &'static str,
+    description: &'static str,
+    example: Option<&'static str>,
+}
+
+impl ExtensionMember {
+    /// Creates the documentation of an extension member.
+    ///
+    /// The `schema_type` is the JSON type of the member, e.g. `"integer"`.
+    pub const fn new(
+        name: &'static str,
+        schema_type: &'static str,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            schema_type,
+            description,
+            example: None,
+        }
+    }
+
+    /// Sets an example value as JSON text.
+    ///
+    /// Text which is not valid JSON is used as a string.
+    pub const fn with_example(mut self, example: &'static str) -> Self {
+        self.example = Some(example);
+        self
+    }
+
+    /// The name of the member
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The JSON type of the member
+    pub const fn schema_type(&self) -> &'static str {
+        self.schema_type
+    }
+
+    /// The description of the member
+    pub const fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// The example value as JSON text
+    pub const fn example(&self) -> Option<&'static str> {
+        self.example
+    }
+
+    /// The example value
+    pub fn example_value(&self) -> Option<Value> {
+        self.example.map(|example| {
+            serde_json::from_str(example).unwrap_or_else(|_| Value::String(example.to_string()))
+        })
+    }
+}
+
+/// Serializes the description of the problem type as served
+/// by a [ProblemTypeDocsHandler] to machines.
+impl Serialize for ProblemType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ProblemType", 8)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("type", self.type_url)?;
+        state.serialize_field("title", self.title)?;
+        state.serialize_field("status", &self.status.as_u16())?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("documentation", self.documentation_url())?;
+        state.serialize_field("extension-members", self.extension_members)?;
+        state.serialize_field("example", &self.example())?;
+        state.end()
+    }
+}
+
+impl Serialize for ExtensionMember {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ExtensionMember", 4)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("type", self.schema_type)?;
+        state.serialize_field("description", self.description)?;
+        state.serialize_field("example", &self.example_value())?;
+        state.end()
+    }
+}
+
+impl From<ProblemType> for HttpApiProblem {
+    fn from(problem_type: ProblemType) -> Self {
+        problem_type.problem()
+    }
+}
+
+impl From<&ProblemType> for HttpApiProblem {
+    fn from(problem_type: &ProblemType) -> Self {
+        problem_type.problem()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoApiError for ProblemType {
+    fn into_api_error(self) -> ApiError {
+        self.api_error()
+    }
+}
+
+/// Holds the [ProblemType]s of a service
+///
+/// The problem types are kept in the order they were registered.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemTypeRegistry {
+    problem_types: Vec<ProblemType>,
+}
+
+impl ProblemTypeRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a problem type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `type` URL is not a URI reference or if a different
+    /// problem type with the same name or `type` URL has already been
+    /// registered. Use [ProblemTypeRegistry::try_register] to handle
+    /// these cases.
+    pub fn register(mut self, problem_type: ProblemType) -> Self {
+        if let Err(err) = self.try_register(problem_type) {
+            panic!("{}", err);
+        }
+        self
+    }
+
+    /// Registers a problem type.
+    ///
+    /// Fails if the `type` URL is not a URI reference or if a different
+    /// problem type with the same name or `type` URL has already been
+    /// registered. Registering the same problem type twice does nothing.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let mut registry = ProblemTypeRegistry::new();
+    /// registry
+    ///     .try_register(ProblemType::new("gone", "/probs/gone", "Gone", StatusCode::GONE))
+    ///     .unwrap();
+    ///
+    /// let result = registry.try_register(ProblemType::new(
+    ///     "removed",
+    ///     "/probs/gone",
+    ///     "Removed",
+    ///     StatusCode::GONE,
+    /// ));
+    /// assert_eq!(
+    ///     Err(ProblemTypeError::DuplicateTypeUrl {
+    ///         type_url: "/probs/gone",
+    ///         registered: "gone",
+    ///     }),
+    ///     result
+    /// );
+    /// ```
+    pub fn try_register(&mut self, problem_type: ProblemType) -> Result<(), ProblemTypeError> {
+        if !uri::is_uri_reference(problem_type.type_url) {
+            return Err(ProblemTypeError::InvalidTypeUrl(problem_type.type_url));
+        }
+
+        for registered in &self.problem_types {
+            if *registered == problem_type {
+                return Ok(());
+            }
+            if registered.name == problem_type.name {
+                return Err(ProblemTypeError::DuplicateName(problem_type.name));
+            }
+            if registered.type_url == problem_type.type_url {
+                return Err(ProblemTypeError::DuplicateTypeUrl {
+                    type_url: problem_type.type_url,
+                    registered: registered.name,
+                });
+            }
+        }
+
+        self.problem_types.push(problem_type);
+        Ok(())
+    }
+
+    /// Returns the problem type with the given name
+    pub fn get(&self, name: &str) -> Option<&ProblemType> {
+        self.problem_types.iter().find(|t| t.name == name)
+    }
+
+    /// Returns the problem type with the given `type` URL
+    pub fn get_by_type_url(&self, type_url: &str) -> Option<&ProblemType> {
+        self.problem_types.iter().find(|t| t.type_url == type_url)
+    }
+
+    /// Returns the registered problem types in the order they were registered
+    pub fn iter(&self) -> impl Iterator<Item = &ProblemType> {
+        self.problem_types.iter()
+    }
+
+    /// The number of registered problem types
+    pub fn len(&self) -> usize {
+        self.problem_types.len()
+    }
+
+    /// Returns `true` if no problem types are registered
+    pub fn is_empty(&self) -> bool {
+        self.problem_types.is_empty()
+    }
+
+    /// Creates an [HttpApiProblem] of the problem type with the given name
+    pub fn problem(&self, name: &str) -> Option<HttpApiProblem> {
+        self.get(name).map(ProblemType::problem)
+    }
+
+    /// Creates an [ApiErrorBuilder] of the problem type with the given name
+    ///
+    /// Requires the `api-error` feature
+    #[cfg(feature = "api-error")]
+    pub fn api_error_builder(&self, name: &str) -> Option<ApiErrorBuilder> {
+        self.get(name).map(ProblemType::api_error_builder)
+    }
+
+    /// Creates an [ApiError] of the problem type with the given name
+    ///
+    /// Requires the `api-error` feature
+    #[cfg(feature = "api-error")]
+    pub fn api_error(&self, name: &str) -> Option<ApiError> {
+        self.get(name).map(ProblemType::api_error)
+    }
+
+    /// Checks that the problem is consistent with the registered
+    /// definition of its type.
+    ///
+    /// Fails if the `type` URL of the problem is not registered or
+    /// if the title differs from the registered title. Problems without
+    /// a `type` URL or with `about:blank` are not checked.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let registry = ProblemTypeRegistry::new()
+    ///     .register(ProblemType::new("gone", "/probs/gone", "Gone", StatusCode::GONE));
+    ///
+    /// let problem = HttpApiProblem::new(StatusCode::GONE).type_url("/probs/gone");
+    /// assert!(registry.check(&problem.clone().title("Gone")).is_ok());
+    /// assert_eq!(
+    ///     Err(ProblemTypeError::TitleMismatch {
+    ///         name: "gone",
+    ///         expected: "Gone",
+    ///         actual: "Removed".to_string(),
+    ///     }),
+    ///     registry.check(&problem.title("Removed"))
+    /// );
+    /// ```
+    pub fn check<Ext>(&self, problem: &HttpApiProblem<Ext>) -> Result<(), ProblemTypeError> {
+        let type_url = match problem.type_url.as_deref() {
+            Some(type_url) if type_url != ABOUT_BLANK => type_url,
+            _ => return Ok(()),
+        };
+
+        let problem_type = match self.get_by_type_url(type_url) {
+            Some(problem_type) => problem_type,
+            None => return Err(ProblemTypeError::UnregisteredTypeUrl(type_url.to_string())),
+        };
+
+        match problem.title.as_deref() {
+            Some(title) if title != problem_type.title => Err(ProblemTypeError::TitleMismatch {
+                name: problem_type.name,
+                expected: problem_type.title,
+                actual: title.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An error of registering a [ProblemType] or of checking a problem
+/// against the [ProblemTypeRegistry]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemTypeError {
+    /// The `type` URL of the problem type is not a URI reference
+    InvalidTypeUrl(&'static str),
+    /// A different problem type with the same name is already registered
+    DuplicateName(&'static str),
+    /// The `type` URL is already registered for a different problem type
+    DuplicateTypeUrl {
+        /// The `type` URL
+        type_url: &'static str,
+        /// The name of the registered problem type
+        registered: &'static str,
+    },
+    /// The `type` URL of the problem is not registered
+    UnregisteredTypeUrl(String),
+    /// The title of the problem differs from the title of its problem type
+    TitleMismatch {
+        /// The name of the problem type
+        name: &'static str,
+        /// The title of the problem type
+        expected: &'static str,
+        /// The title of the problem
+        actual: String,
+    },
+}
+
+impl fmt::Display for ProblemTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemTypeError::InvalidTypeUrl(type_url) => {
+                write!(f, "the type URL '{}' is not a URI reference", type_url)
+            }
+            ProblemTypeError::DuplicateName(name) => write!(
+                f,
+                "a different problem type named '{}' is already registered",
+                name
+            ),
+            ProblemTypeError::DuplicateTypeUrl {
+                type_url,
+                registered,
+            } => write!(
+                f,
+                "the type URL '{}' is already registered for the problem type '{}'",
+                type_url, registered
+            ),
+            ProblemTypeError::UnregisteredTypeUrl(type_url) => {
+                write!(f, "the type URL '{}' is not registered", type_url)
+            }
+            ProblemTypeError::TitleMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "the title of the problem type '{}' is '{}' but was '{}'",
+                name, expected, actual
+            ),
+        }
+    }
+}
+
+impl Error for ProblemTypeError {}
+
+impl FromIterator<ProblemType> for ProblemTypeRegistry {
+    /// # Panics
+    ///
+    /// Panics like [ProblemTypeRegistry::register] on conflicting problem types.
+    fn from_iter<T: IntoIterator<Item = ProblemType>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(ProblemTypeRegistry::new(), ProblemTypeRegistry::register)
+    }
+}
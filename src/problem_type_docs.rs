@@ -0,0 +1,389 @@
+//! Documentation pages for problem types
+//!
+//! RFC9457 recommends that the `type` URL of a problem resolves to
+//! human-readable documentation. [ProblemTypeDocs] generates a Markdown or
+//! HTML page for every [ProblemType] of a [ProblemTypeRegistry] containing
+//! its title, status, description, extension members and an example, as
+//! well as an index page linking to all of them.
+//!
+//! The page of a problem type is named after the name of the problem
+//! type, e.g. `out-of-credit.html`, and the index page is `index.html`.
+//! Characters of the name other than ASCII letters, digits, `-` and `_`
+//! are replaced by `_` so that pages are always written into the directory
+//! passed to [ProblemTypeDocs::write]. [ProblemTypeDocs::write] fails instead
+//! of overwriting a page if two pages get the same file name, e.g. for the
+//! problem types `a.b` and `a_b` or a problem type named `index`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+//!     "out-of-credit",
+//!     "https://example.com/probs/out-of-credit",
+//!     "You do not have enough credit.",
+//!     StatusCode::FORBIDDEN,
+//! );
+//!
+//! let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+//! let pages = ProblemTypeDocs::new(DocsFormat::Markdown).pages(&registry);
+//!
+//! assert_eq!("index.md", pages[0].file_name);
+//! assert_eq!("out-of-credit.md", pages[1].file_name);
+//! assert!(pages[1].content.starts_with("# You do not have enough credit."));
+//! ```
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+use super::negotiation::escape_html;
+use super::*;
+
+/// The formats of documentation pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocsFormat {
+    /// Markdown
+    Markdown,
+    /// A standalone HTML document
+    Html,
+}
+
+impl DocsFormat {
+    /// The file extension of pages in this format
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// A generated documentation page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsPage {
+    /// The file name of the page, e.g. `out-of-credit.html`
+    pub file_name: String,
+    /// The content of the page
+    pub content: String,
+}
+
+/// Generates documentation pages for problem types
+#[derive(Debug, Clone)]
+pub struct ProblemTypeDocs {
+    format: DocsFormat,
+    title: String,
+}
+
+impl ProblemTypeDocs {
+    /// Creates a generator for pages in the given format.
+    ///
+    /// The title of the index page is "Problem Types".
+    pub fn new(format: DocsFormat) -> Self {
+        Self {
+            format,
+            title: "Problem Types".to_string(),
+        }
+    }
+
+    /// Sets the title of the index page
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// The format of the generated pages
+    pub fn format(&self) -> DocsFormat {
+        self.format
+    }
+
+    /// The file name of the page of the problem type
+    ///
+    /// Characters of the name other than ASCII letters, digits, `-` and `_`
+    /// are replaced by `_`.
+    pub fn file_name(&self, problem_type: &ProblemType) -> String {
+        let name: String = problem_type
+            .name()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let name = if name.is_empty() { "_" } else { &name };
+
+        format!("{}.{}", name, self.format.file_extension())
+    }
+
+    /// Renders the page of the problem type
+    pub fn page(&self, problem_type: &ProblemType) -> String {
+        match self.format {
+            DocsFormat::Markdown => markdown_page(problem_type),
+            DocsFormat::Html => html_page(problem_type),
+        }
+    }
+
+    /// Renders the index page of the registered problem types
+    pub fn index(&self, registry: &ProblemTypeRegistry) -> String {
+        match self.format {
+            DocsFormat::Markdown => self.markdown_index(registry),
+            DocsFormat::Html => self.html_index(registry),
+        }
+    }
+
+    /// Renders the index page followed by the pages of all
+    /// registered problem types
+    pub fn pages(&self, registry: &ProblemTypeRegistry) -> Vec<DocsPage> {
+        let index = DocsPage {
+            file_name: format!("index.{}", self.format.file_extension()),
+            content: self.index(registry),
+        };
+
+        let pages = registry.iter().map(|problem_type| DocsPage {
+            file_name: self.file_name(problem_type),
+            content: self.page(problem_type),
+        });
+
+        std::iter::once(index).chain(pages).collect()
+    }
+
+    /// Writes all pages into the directory.
+    ///
+    /// The directory is created if it does not exist and existing
+    /// pages are overwritten.
+    ///
+    /// Fails with [io::ErrorKind::InvalidInput] without writing any page
+    /// if two pages have the same file name. File names which only differ
+    /// in case are the same on case-insensitive file systems and also fail.
+    pub fn write<P: AsRef<Path>>(&self, registry: &ProblemTypeRegistry, dir: P) -> io::Result<()> {
+        let pages = self.pages(registry);
+
+        let mut file_names = HashSet::new();
+        for page in &pages {
+            if !file_names.insert(page.file_name.to_ascii_lowercase()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("more than one page has the file name '{}'", page.file_name),
+                ));
+            }
+        }
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for page in pages {
+            std::fs::write(dir.join(page.file_name), page.content)?;
+        }
+        Ok(())
+    }
+
+    fn markdown_index(&self, registry: &ProblemTypeRegistry) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# {}\n", escape_markdown(&self.title));
+        md.push_str("| Problem type | Status | Type |\n|---|---|---|\n");
+        for problem_type in registry.iter() {
+            let _ = writeln!(
+                md,
+                "| [{}]({}) | {} | {} |",
+                escape_markdown(problem_type.title()),
+                self.file_name(problem_type),
+                status_line(problem_type.status()),
+                escape_markdown_cell(&markdown_code_span(problem_type.type_url()))
+            );
+        }
+        md
+    }
+
+    fn html_index(&self, registry: &ProblemTypeRegistry) -> String {
+        let mut html = html_head(&self.title);
+        html.push_str("<table>\n<tr><th>Problem type</th><th>Status</th><th>Type</th></tr>\n");
+        for problem_type in registry.iter() {
+            let _ = writeln!(
+                html,
+                "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td><code>{}</code></td></tr>",
+                escape_html(&self.file_name(problem_type)),
+                escape_html(problem_type.title()),
+                status_line(problem_type.status()),
+                escape_html(problem_type.type_url())
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn markdown_page(problem_type: &ProblemType) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# {}\n", escape_markdown(problem_type.title()));
+    if let Some(description) = problem_type.description() {
+        let _ = writeln!(md, "{}\n", escape_markdown(description));
+    }
+    let _ = writeln!(
+        md,
+        "- **type**: {}",
+        markdown_code_span(problem_type.type_url())
+    );
+    let _ = writeln!(md, "- **status**: {}", status_line(problem_type.status()));
+    if problem_type.documentation_url() != problem_type.type_url() {
+        let _ = writeln!(
+            md,
+            "- **documentation**: {}",
+            markdown_url(problem_type.documentation_url())
+        );
+    }
+
+    if !problem_type.extension_members().is_empty() {
+        md.push_str("\n## Extension members\n\n");
+        md.push_str("| Name | Type | Description |\n|---|---|---|\n");
+        for member in problem_type.extension_members() {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} |",
+                escape_markdown_cell(&markdown_code_span(member.name())),
+                escape_markdown(member.schema_type()),
+                escape_markdown(member.description())
+            );
+        }
+    }
+
+    let _ = writeln!(
+        md,
+        "\n## Example\n\n```json\n{}\n```",
+        example_json(problem_type)
+    );
+    md
+}
+
+fn html_page(problem_type: &ProblemType) -> String {
+    let mut html = html_head(problem_type.title());
+    if let Some(description) = problem_type.description() {
+        let _ = writeln!(html, "<p>{}</p>", escape_html(description));
+    }
+    html.push_str("<dl>\n");
+    let _ = writeln!(
+        html,
+        "<dt>type</dt><dd><code>{}</code></dd>",
+        escape_html(problem_type.type_url())
+    );
+    let _ = writeln!(
+        html,
+        "<dt>status</dt><dd>{}</dd>",
+        status_line(problem_type.status())
+    );
+    if problem_type.documentation_url() != problem_type.type_url() {
+        let documentation_url = problem_type.documentation_url();
+        if uri::is_linkable(documentation_url) {
+            let _ = writeln!(
+                html,
+                "<dt>documentation</dt><dd><a href=\"{0}\">{0}</a></dd>",
+                escape_html(documentation_url)
+            );
+        } else {
+            let _ = writeln!(
+                html,
+                "<dt>documentation</dt><dd><code>{}</code></dd>",
+                escape_html(documentation_url)
+            );
+        }
+    }
+    html.push_str("</dl>\n");
+
+    if !problem_type.extension_members().is_empty() {
+        html.push_str("<h2>Extension members</h2>\n<table>\n");
+        html.push_str("<tr><th>Name</th><th>Type</th><th>Description</th></tr>\n");
+        for member in problem_type.extension_members() {
+            let _ = writeln!(
+                html,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                escape_html(member.name()),
+                escape_html(member.schema_type()),
+                escape_html(member.description())
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    let _ = writeln!(
+        html,
+        "<h2>Example</h2>\n<pre><code>{}</code></pre>",
+        escape_html(&example_json(problem_type))
+    );
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    )
+}
+
+/// `403 Forbidden`
+fn status_line(status: StatusCode) -> String {
+    match status.canonical_reason() {
+        Some(reason) => format!("{} {}", status.as_u16(), reason),
+        None => status.as_u16().to_string(),
+    }
+}
+
+/// The members are sorted so that the pages only change if the problem type does.
+fn example_json(problem_type: &ProblemType) -> String {
+    let example = serde_json::to_value(problem_type.example()).unwrap_or_default();
+    serde_json::to_string_pretty(&example).unwrap_or_default()
+}
+
+/// Escapes text so that it is neither Markdown nor HTML and
+/// stays on one line, e.g. in a heading or a table cell
+fn escape_markdown(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\\' | '`' | '*' | '_' | '{' | '}' | '[' | ']' | '>' | '#' | '+' | '-' | '!' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes the pipes of a code span in a table cell
+fn escape_markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A code span delimited by more backticks than `s` contains in a row
+fn markdown_code_span(s: &str) -> String {
+    let s = s.replace('\n', " ");
+    let longest_run = s
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run + 1);
+    if s.starts_with(['`', ' ']) || s.ends_with(['`', ' ']) {
+        format!("{0} {1} {0}", fence, s)
+    } else {
+        format!("{0}{1}{0}", fence, s)
+    }
+}
+
+/// A link if [uri::is_linkable] allows it, otherwise a code span
+fn markdown_url(url: &str) -> String {
+    if !uri::is_linkable(url) {
+        markdown_code_span(url)
+    } else if uri::is_absolute(url) {
+        format!("<{}>", url)
+    } else {
+        format!("[{}](<{}>)", markdown_code_span(url), url)
+    }
+}
@@ -0,0 +1,259 @@
+//! Serving the documentation of problem types
+//!
+//! A [ProblemTypeDocsHandler] serves the documentation of every registered
+//! [ProblemType] at the path of its `type` URL, so that the `type` URLs of
+//! a service resolve without deploying a separate documentation site.
+//!
+//! Clients accepting `text/html` (i.e. browsers) get the HTML page
+//! generated by [ProblemTypeDocs]. All other clients get the problem
+//! type as `application/json`. Unknown paths are answered with a
+//! `404 - Not Found` problem.
+//!
+//! The handler itself works with [http] types. With the feature `axum-extract`
+//! it creates a `Router` and with the feature `actix-web` it configures an `App`.
+//! Path segments which the router would take for a parameter (e.g. `:id` for
+//! axum or `{id}` for actix-web) are routed as parameters and the handler only
+//! answers requests for the exact path of a `type` URL.
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+//!     "out-of-credit",
+//!     "https://example.com/probs/out-of-credit",
+//!     "You do not have enough credit.",
+//!     StatusCode::FORBIDDEN,
+//! );
+//!
+//! let handler = ProblemTypeDocsHandler::new(ProblemTypeRegistry::new().register(OUT_OF_CREDIT));
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert(http::header::ACCEPT, "text/html".parse().unwrap());
+//! let response = handler.handle("/probs/out-of-credit", &headers);
+//!
+//! assert_eq!(StatusCode::OK, response.status());
+//! assert_eq!("text/html; charset=utf-8", response.headers()["content-type"]);
+//!
+//! let response = handler.handle("/probs/out-of-credit", &http::HeaderMap::new());
+//! assert_eq!("application/json", response.headers()["content-type"]);
+//! ```
+use std::sync::Arc;
+
+use http::header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, VARY};
+
+use super::*;
+
+/// Serves the documentation of the problem types of a [ProblemTypeRegistry]
+/// at the paths of their `type` URLs.
+#[derive(Debug, Clone)]
+pub struct ProblemTypeDocsHandler {
+    registry: Arc<ProblemTypeRegistry>,
+    docs: ProblemTypeDocs,
+    negotiator: Negotiator,
+}
+
+impl ProblemTypeDocsHandler {
+    /// Creates a handler for the registered problem types.
+    pub fn new(registry: ProblemTypeRegistry) -> Self {
+        Self {
+            registry: Arc::new(registry),
+            docs: ProblemTypeDocs::new(DocsFormat::Html),
+            negotiator: Negotiator::new(ProblemFormat::Json)
+                .formats([ProblemFormat::Json, ProblemFormat::Html]),
+        }
+    }
+
+    /// The registry of the served problem types
+    pub fn registry(&self) -> &ProblemTypeRegistry {
+        &self.registry
+    }
+
+    /// The paths of the `type` URLs of all registered problem types
+    ///
+    /// The scheme and authority of absolute `type` URLs are ignored and
+    /// relative `type` URLs are served relative to the root. Problem types
+    /// whose `type` URL has no path (e.g. `about:blank`) are not served.
+    /// If the `type` URLs of problem types differ only by their authority,
+    /// the first registered problem type is served.
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        for path in self
+            .registry
+            .iter()
+            .filter_map(|problem_type| type_url_path(problem_type.type_url()))
+        {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Returns the problem type served at the given path
+    pub fn problem_type(&self, path: &str) -> Option<&ProblemType> {
+        self.registry
+            .iter()
+            .find(|problem_type| type_url_path(problem_type.type_url()).as_deref() == Some(path))
+    }
+
+    /// Creates the response for a request of the given path.
+    pub fn handle(&self, path: &str, headers: &HeaderMap) -> http::Response<String> {
+        let problem_type = match self.problem_type(path) {
+            Some(problem_type) => problem_type,
+            None => {
+                let problem = HttpApiProblem::with_title(StatusCode::NOT_FOUND)
+                    .detail(format!("There is no problem type at '{}'", path));
+                return Negotiator::default().to_http_response(&problem, headers);
+            }
+        };
+
+        let (content_type, body) = match self.negotiator.negotiate_headers(headers) {
+            ProblemFormat::Html => (
+                ProblemFormat::Html.content_type(),
+                self.docs.page(problem_type),
+            ),
+            _ => (
+                ProblemFormat::Json.content_type(),
+                serde_json::to_string(problem_type).unwrap_or_default(),
+            ),
+        };
+
+        let length = body.len();
+        let mut response = http::Response::new(body);
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(length));
+        headers.insert(VARY, HeaderValue::from_static("Accept"));
+
+        response
+    }
+
+    /// Creates an axum `Router` with a `GET` route for every path.
+    ///
+    /// Merge it into the `Router` of the application.
+    ///
+    /// Requires the `axum-extract` feature
+    #[cfg(feature = "axum-extract")]
+    pub fn into_axum_router<S>(self) -> axum_crate::Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let patterns = self.route_patterns(
+            |segment| segment.contains([':', '*']),
+            |idx| format!(":segment{}", idx),
+        );
+
+        patterns
+            .into_iter()
+            .fold(axum_crate::Router::new(), |router, pattern| {
+                let handler = self.clone();
+                router.route(
+                    &pattern,
+                    axum_crate::routing::get(
+                        move |uri: http::Uri, headers: HeaderMap| async move {
+                            handler
+                                .handle(uri.path(), &headers)
+                                .map(axum_core::body::Body::from)
+                        },
+                    ),
+                )
+            })
+    }
+
+    /// Adds a `GET` route for every path to an actix-web `App`.
+    ///
+    /// Pass it to `App::configure`.
+    ///
+    /// Requires the `actix-web` feature
+    #[cfg(feature = "actix-web")]
+    pub fn configure_actix(&self, config: &mut actix_web::web::ServiceConfig) {
+        let patterns = self.route_patterns(
+            |segment| segment.contains(['{', '}']),
+            |idx| format!("{{segment{}}}", idx),
+        );
+
+        for pattern in patterns {
+            let handler = self.clone();
+            config.route(
+                &pattern,
+                actix_web::web::get().to(move |request: actix_web::HttpRequest| {
+                    let response = handler.handle(request.uri().path(), &actix_headers(&request));
+                    async move { to_actix_response(response) }
+                }),
+            );
+        }
+    }
+
+    /// The route patterns of the paths. Segments containing characters
+    /// with a special meaning to the router are replaced by a parameter.
+    #[cfg(any(feature = "axum-extract", feature = "actix-web"))]
+    fn route_patterns(
+        &self,
+        is_special: impl Fn(&str) -> bool,
+        parameter: impl Fn(usize) -> String,
+    ) -> Vec<String> {
+        let mut patterns = Vec::new();
+        for path in self.paths() {
+            let pattern = path
+                .split('/')
+                .enumerate()
+                .map(|(idx, segment)| {
+                    if is_special(segment) {
+                        parameter(idx)
+                    } else {
+                        segment.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        patterns
+    }
+}
+
+/// The path of a `type` URL or `None` if it has none
+fn type_url_path(type_url: &str) -> Option<String> {
+    let path = uri::served_path(type_url)?;
+
+    if path.is_empty() || path == "/" {
+        None
+    } else if path.starts_with('/') {
+        Some(path.to_string())
+    } else {
+        Some(format!("/{}", path))
+    }
+}
+
+/// actix-web uses an older version of [http]
+#[cfg(feature = "actix-web")]
+fn actix_headers(request: &actix_web::HttpRequest) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(accept) = request
+        .headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|v| HeaderValue::from_bytes(v.as_bytes()).ok())
+    {
+        headers.insert(http::header::ACCEPT, accept);
+    }
+    headers
+}
+
+#[cfg(feature = "actix-web")]
+fn to_actix_response(response: http::Response<String>) -> actix_web::HttpResponse {
+    let status = actix_web::http::StatusCode::from_u16(response.status().as_u16())
+        .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+    let mut builder = actix_web::HttpResponse::build(status);
+    for (name, value) in response.headers() {
+        if name != CONTENT_LENGTH {
+            builder.append_header((name.as_str(), value.as_bytes()));
+        }
+    }
+    builder.body(response.into_body())
+}
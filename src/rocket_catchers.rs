@@ -0,0 +1,99 @@
+//! Catchers and form errors for [rocket]
+//!
+//! [problem_catchers] replaces rocket's default HTML error pages with
+//! problems for the status of the error.
+//!
+//! The [Errors](rocket::form::Errors) of rocket's `Form` data guard convert into
+//! a [ValidationProblem] (and an `ApiError` with the feature `api-error`).
+//! The status is the status rocket would use for the errors and every
+//! named field becomes an [InvalidParam].
+//!
+//! #Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//! use rocket::form::{self, Form};
+//! use rocket::http::Status;
+//! use rocket::{post, routes, FromForm};
+//!
+//! #[derive(FromForm)]
+//! struct User<'r> {
+//!     name: &'r str,
+//! }
+//!
+//! #[post("/users", data = "<user>")]
+//! fn create_user(user: Result<Form<User<'_>>, form::Errors<'_>>) -> Result<Status, HttpApiProblem> {
+//!     let user = user.map_err(HttpApiProblem::from)?;
+//!     println!("created {}", user.name);
+//!     Ok(Status::Created)
+//! }
+//!
+//! let rocket = rocket::build()
+//!     .mount("/", routes![create_user])
+//!     .register("/", problem_catchers());
+//! ```
+use rocket::catcher::{BoxFuture, Catcher};
+use rocket::form::Errors;
+use rocket::http::Status;
+use rocket::Request;
+
+use super::*;
+
+/// Creates the catchers which respond to every error with an [HttpApiProblem]
+/// for its status.
+///
+/// The `instance` of the problem is the path of the request.
+///
+/// Register them with `Rocket::register`.
+///
+/// Requires the `rocket` feature
+pub fn problem_catchers() -> Vec<Catcher> {
+    vec![Catcher::new(None, problem_catcher)]
+}
+
+fn problem_catcher<'r>(status: Status, req: &'r Request<'_>) -> BoxFuture<'r> {
+    let status = StatusCode::from_u16(status.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let problem =
+        HttpApiProblem::with_title_and_type(status).instance(req.uri().path().to_string());
+    Box::pin(async move { Ok(problem.to_rocket_response()) })
+}
+
+impl From<&Errors<'_>> for ValidationProblem {
+    fn from(errors: &Errors<'_>) -> Self {
+        let status = StatusCode::from_u16(errors.status().code).unwrap_or(StatusCode::BAD_REQUEST);
+
+        let mut details = Vec::new();
+        let mut problem = ValidationProblem::new().status(status);
+        for error in errors.iter() {
+            match error.name {
+                Some(ref name) => problem = problem.query(name.to_string(), error.to_string()),
+                None => details.push(error.to_string()),
+            }
+        }
+
+        if details.is_empty() {
+            problem
+        } else {
+            problem.detail(details.join(", "))
+        }
+    }
+}
+
+impl From<Errors<'_>> for ValidationProblem {
+    fn from(errors: Errors<'_>) -> Self {
+        ValidationProblem::from(&errors)
+    }
+}
+
+impl From<Errors<'_>> for HttpApiProblem {
+    fn from(errors: Errors<'_>) -> Self {
+        ValidationProblem::from(&errors).into_http_api_problem()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoApiError for Errors<'_> {
+    fn into_api_error(self) -> ApiError {
+        ValidationProblem::from(&self).into_api_error()
+    }
+}
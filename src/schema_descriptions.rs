@@ -0,0 +1,22 @@
+//! The names and descriptions shared by the schemas of the features
+//! `json-schema`, `aide`, `poem-openapi` and `utoipa`
+
+/// The name of the schema of an untyped [HttpApiProblem](crate::HttpApiProblem)
+pub(crate) const SCHEMA_NAME: &str = "HttpApiProblem";
+
+pub(crate) const PROBLEM: &str = "Description of a problem that can be returned by an HTTP API based on [RFC9457](https://www.rfc-editor.org/rfc/rfc9457)";
+
+pub(crate) const TYPE: &str = "A [RFC3986 URI reference](https://tools.ietf.org/html/rfc3986) that identifies the problem type. When dereferenced, it may provide human-readable documentation for the problem type.";
+
+pub(crate) const STATUS: &str = "The HTTP status code [RFC7231, Section 6](https://tools.ietf.org/html/rfc7231#section-6) generated by the origin server for this occurrence of the problem.";
+
+pub(crate) const TITLE: &str = "A short, human-readable summary of the problem type. It SHOULD NOT change from occurrence to occurrence of the problem.";
+
+pub(crate) const DETAIL: &str =
+    "A human-readable explanation specific to this occurrence of the problem.";
+
+pub(crate) const INSTANCE: &str = "A URI reference that identifies the specific occurrence of the problem.  It may or may not yield further information if dereferenced.";
+
+/// The description of a response with a problem body
+#[cfg(any(feature = "aide", feature = "poem-openapi", feature = "utoipa"))]
+pub(crate) const RESPONSE: &str = "A problem as described in RFC 9457";
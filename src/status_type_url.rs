@@ -0,0 +1,105 @@
+//! Derivation of `type` URLs from [StatusCode]s.
+//!
+//! [HttpApiProblem::with_title_and_type] and the conversions
+//! of `ApiError` into an [HttpApiProblem] set a `type_url` derived
+//! from the status code if no other `type_url` was given. How it
+//! is derived can be configured process wide with
+//! [StatusTypeUrl::set_default] or per call by passing a [StatusTypeUrl].
+use std::sync::RwLock;
+
+use super::*;
+
+static DEFAULT_STATUS_TYPE_URL: RwLock<StatusTypeUrl> = RwLock::new(StatusTypeUrl::HttpStatuses);
+
+/// Defines how a `type_url` is derived from a [StatusCode]
+///
+/// # Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// let p = HttpApiProblem::with_title_and_type_from(
+///     StatusCode::NOT_FOUND,
+///     &StatusTypeUrl::Base("https://docs.example.com/errors".to_string()),
+/// );
+/// assert_eq!(Some("https://docs.example.com/errors/404"), p.type_url.as_deref());
+///
+/// let p = HttpApiProblem::with_title_and_type_from(StatusCode::NOT_FOUND, &StatusTypeUrl::Omit);
+/// assert_eq!(None, p.type_url);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum StatusTypeUrl {
+    /// `https://httpstatuses.com/<code>`
+    ///
+    /// This is the default.
+    #[default]
+    HttpStatuses,
+    /// `https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/<code>`
+    Mdn,
+    /// `about:blank` as recommended by
+    /// [RFC9457](https://www.rfc-editor.org/rfc/rfc9457#section-4.2.1)
+    AboutBlank,
+    /// The status code appended to the given base as a path segment
+    Base(String),
+    /// Do not set a `type_url`
+    Omit,
+}
+
+impl StatusTypeUrl {
+    /// Sets the process wide default.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// StatusTypeUrl::set_default(StatusTypeUrl::AboutBlank);
+    ///
+    /// let p = HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND);
+    /// assert_eq!(Some("about:blank"), p.type_url.as_deref());
+    /// ```
+    pub fn set_default(status_type_url: StatusTypeUrl) {
+        let mut current = DEFAULT_STATUS_TYPE_URL
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *current = status_type_url;
+    }
+
+    /// Returns the process wide default.
+    pub fn get_default() -> StatusTypeUrl {
+        DEFAULT_STATUS_TYPE_URL
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Returns the `type_url` for the given [StatusCode].
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// assert_eq!(
+    ///     Some("https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/503".to_string()),
+    ///     StatusTypeUrl::Mdn.type_url(StatusCode::SERVICE_UNAVAILABLE)
+    /// );
+    /// ```
+    pub fn type_url(&self, status: StatusCode) -> Option<String> {
+        match self {
+            StatusTypeUrl::HttpStatuses => {
+                Some(format!("https://httpstatuses.com/{}", status.as_u16()))
+            }
+            StatusTypeUrl::Mdn => Some(format!(
+                "https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/{}",
+                status.as_u16()
+            )),
+            StatusTypeUrl::AboutBlank => Some(ABOUT_BLANK.to_string()),
+            StatusTypeUrl::Base(base) => {
+                let separator = if base.ends_with('/') { "" } else { "/" };
+                Some(format!("{}{}{}", base, separator, status.as_u16()))
+            }
+            StatusTypeUrl::Omit => None,
+        }
+    }
+}
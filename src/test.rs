@@ -55,3 +55,3595 @@ mod serialization {
         assert_eq!(prob.status, None);
     }
 }
+
+mod rfc9457 {
+    use crate::{uri, HttpApiProblem, ABOUT_BLANK};
+    use http::StatusCode;
+
+    #[test]
+    fn missing_type_is_about_blank_after_deserialization() {
+        let json = r#"{"status": 404}"#;
+
+        let prob: HttpApiProblem = serde_json::from_str(json).unwrap();
+        let prob = prob.into_rfc9457();
+
+        assert_eq!(prob.type_url.as_deref(), Some(ABOUT_BLANK));
+        assert_eq!(prob.title.as_deref(), Some("Not Found"));
+    }
+
+    #[test]
+    fn title_is_not_overwritten() {
+        let prob = HttpApiProblem::new(StatusCode::NOT_FOUND)
+            .title("Nothing here")
+            .into_rfc9457();
+
+        assert_eq!(prob.title.as_deref(), Some("Nothing here"));
+    }
+
+    #[test]
+    fn resolve_relative_references() {
+        // RFC3986, Section 5.4
+        let base = "http://a/b/c/d;p?q";
+
+        assert_eq!(uri::resolve(base, "g:h"), "g:h");
+        assert_eq!(uri::resolve(base, "g"), "http://a/b/c/g");
+        assert_eq!(uri::resolve(base, "./g"), "http://a/b/c/g");
+        assert_eq!(uri::resolve(base, "g/"), "http://a/b/c/g/");
+        assert_eq!(uri::resolve(base, "/g"), "http://a/g");
+        assert_eq!(uri::resolve(base, "//g"), "http://g");
+        assert_eq!(uri::resolve(base, "?y"), "http://a/b/c/d;p?y");
+        assert_eq!(uri::resolve(base, "g?y"), "http://a/b/c/g?y");
+        assert_eq!(uri::resolve(base, "#s"), "http://a/b/c/d;p?q#s");
+        assert_eq!(uri::resolve(base, ""), "http://a/b/c/d;p?q");
+        assert_eq!(uri::resolve(base, "."), "http://a/b/c/");
+        assert_eq!(uri::resolve(base, ".."), "http://a/b/");
+        assert_eq!(uri::resolve(base, "../g"), "http://a/b/g");
+        assert_eq!(uri::resolve(base, "../../g"), "http://a/g");
+        assert_eq!(uri::resolve(base, "../../../g"), "http://a/g");
+        assert_eq!(uri::resolve(base, "/./g"), "http://a/g");
+        assert_eq!(uri::resolve(base, "g;x=1/../y"), "http://a/b/c/y");
+    }
+}
+
+#[cfg(feature = "xml")]
+mod xml {
+    use crate::HttpApiProblem;
+    use http::StatusCode;
+    use serde_json::json;
+
+    #[test]
+    fn xml_round_trip() {
+        let prob = HttpApiProblem::with_title_and_type(StatusCode::BAD_REQUEST)
+            .detail("a < b & c")
+            .instance("/account/12345/msgs/abc")
+            .value("balance", &30)
+            .value("ratio", &0.5)
+            .value("ok", &false)
+            .value("accounts", &vec!["/account/1", "/account/2"])
+            .value("nested", &json!({"name": "age", "reason": "too old"}));
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert_eq!(parsed, prob);
+    }
+
+    #[test]
+    fn invalid_xml_names_are_omitted() {
+        let prob = HttpApiProblem::new(StatusCode::BAD_REQUEST).value("1abc", &1);
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert!(parsed.additional_fields().is_empty());
+    }
+
+    #[test]
+    fn deeply_nested_documents_are_rejected() {
+        let nested = |depth: usize| {
+            format!(
+                r#"<problem xmlns="urn:ietf:rfc:7807">{}{}</problem>"#,
+                "<a>".repeat(depth),
+                "</a>".repeat(depth)
+            )
+        };
+
+        assert!(HttpApiProblem::from_xml_str(&nested(127)).is_ok());
+        assert!(HttpApiProblem::from_xml_str(&nested(128)).is_err());
+        assert!(HttpApiProblem::from_xml_str(&nested(100_000)).is_err());
+    }
+
+    #[test]
+    fn wrong_root_element_is_rejected() {
+        let xml = r#"<error><status>400</status></error>"#;
+
+        assert!(HttpApiProblem::from_xml_str(xml).is_err());
+    }
+
+    #[test]
+    fn wrong_namespace_is_rejected() {
+        let missing = r#"<problem><status>400</status></problem>"#;
+        let other = r#"<problem xmlns="urn:example"><status>400</status></problem>"#;
+
+        assert!(HttpApiProblem::from_xml_str(missing).is_err());
+        assert!(HttpApiProblem::from_xml_str(other).is_err());
+    }
+
+    #[test]
+    fn prefixed_root_element_is_accepted() {
+        let xml = r#"<p:problem xmlns:p="urn:ietf:rfc:7807"><p:status>400</p:status></p:problem>"#;
+
+        let parsed = HttpApiProblem::from_xml_str(xml).unwrap();
+
+        assert_eq!(parsed.status, Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn null_and_empty_values_round_trip() {
+        let prob = HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .value("nothing", &json!(null))
+            .value("no_items", &json!([]))
+            .value("no_members", &json!({}))
+            .value("empty", &"")
+            .value("nested", &json!({"list": [null, [], {}], "inner": {}}));
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert_eq!(parsed, prob);
+    }
+
+    #[test]
+    fn non_ascii_names() {
+        let mut prob = HttpApiProblem::new(StatusCode::BAD_REQUEST);
+        prob.set_value("éé", &1);
+        prob.set_value("xé", &2);
+        prob.set_value("größe", &3);
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert_eq!(parsed, prob);
+    }
+
+    #[test]
+    fn text_is_kept_verbatim() {
+        let prob = HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .title(" ")
+            .detail("  x \r\n\t")
+            .value("padded", &" 30 ")
+            .value("lines", &vec!["\n", " a "]);
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert_eq!(parsed, prob);
+    }
+
+    #[test]
+    fn objects_with_members_named_i_round_trip() {
+        let prob = HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .value("single", &json!({"i": 1}))
+            .value("nested", &json!([{"i": [{"i": "x"}]}]));
+
+        let parsed = HttpApiProblem::from_xml_str(&prob.xml_string()).unwrap();
+
+        assert_eq!(parsed, prob);
+    }
+
+    #[test]
+    fn unmarked_sequences_of_i_are_arrays() {
+        let xml = r#"<problem xmlns="urn:ietf:rfc:7807"><list><i>a</i><i>b</i></list><obj><a>1</a></obj></problem>"#;
+
+        let parsed = HttpApiProblem::from_xml_str(xml).unwrap();
+
+        assert_eq!(parsed.json_value("list"), Some(&json!(["a", "b"])));
+        assert_eq!(parsed.json_value("obj"), Some(&json!({"a": 1})));
+    }
+
+    #[test]
+    fn control_characters_are_replaced() {
+        let prob = HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .detail("a\u{0}b\u{1b}c")
+            .value("bell", &"\u{7}");
+
+        let xml = prob.xml_string();
+
+        assert!(!xml.chars().any(|c| c.is_control() && !"\t\n\r".contains(c)));
+        let parsed = HttpApiProblem::from_xml_str(&xml).unwrap();
+        assert_eq!(parsed.detail.as_deref(), Some("a\u{FFFD}b\u{FFFD}c"));
+        assert_eq!(parsed.json_value("bell"), Some(&json!("\u{FFFD}")));
+    }
+}
+
+mod negotiation {
+    use crate::{HttpApiProblem, Negotiator, ProblemFormat};
+    use http::StatusCode;
+
+    #[test]
+    fn most_specific_range_wins() {
+        let negotiator = Negotiator::new(ProblemFormat::ProblemJson);
+
+        let format = negotiator.negotiate(Some("text/*;q=0.9, text/html;q=0.1, */*;q=0.5"));
+
+        assert_eq!(format, ProblemFormat::Text);
+    }
+
+    #[test]
+    fn excluded_formats_fall_back_to_default() {
+        let negotiator = Negotiator::new(ProblemFormat::Json)
+            .formats([ProblemFormat::Json, ProblemFormat::ProblemJson]);
+
+        assert_eq!(negotiator.negotiate(Some("text/html")), ProblemFormat::Json);
+        assert_eq!(
+            negotiator.negotiate(Some("application/problem+json, application/json;q=0")),
+            ProblemFormat::ProblemJson
+        );
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let problem = HttpApiProblem::new(StatusCode::BAD_REQUEST).title("<script>");
+
+        let html = problem.render(ProblemFormat::Html);
+
+        assert!(html.contains("<h1>&lt;script&gt;</h1>"));
+    }
+
+    #[test]
+    fn only_http_and_relative_types_are_links() {
+        let html = |type_url: &str| {
+            HttpApiProblem::new(StatusCode::BAD_REQUEST)
+                .type_url(type_url)
+                .render(ProblemFormat::Html)
+        };
+
+        assert!(html("https://example.com/probs/a")
+            .contains("<a href=\"https://example.com/probs/a\">"));
+        assert!(html("/probs/a").contains("<a href=\"/probs/a\">"));
+        for type_url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "data:text/html,x",
+        ] {
+            let html = html(type_url);
+            assert!(!html.contains("<a "));
+            assert!(html.contains("<dt>type</dt><dd>"));
+        }
+    }
+
+    #[test]
+    fn highest_quality_wins() {
+        let negotiator = Negotiator::new(ProblemFormat::ProblemJson);
+
+        assert_eq!(
+            negotiator.negotiate(Some("text/html;q=0.4, text/plain;q=0.6")),
+            ProblemFormat::Text
+        );
+        assert_eq!(
+            negotiator.negotiate(Some("application/json;q=1.0, text/plain;Q=0.999")),
+            ProblemFormat::Json
+        );
+        assert_eq!(
+            negotiator.negotiate(Some("text/plain;charset=utf-8;q=0.2, text/html;q=0.3")),
+            ProblemFormat::Html
+        );
+    }
+
+    #[test]
+    fn default_format_is_preferred_with_the_same_quality() {
+        let negotiator = Negotiator::new(ProblemFormat::Text);
+
+        assert_eq!(negotiator.negotiate(Some("*/*")), ProblemFormat::Text);
+        assert_eq!(
+            negotiator.negotiate(Some("text/html, text/plain")),
+            ProblemFormat::Text
+        );
+        assert_eq!(
+            negotiator.negotiate(Some("application/*")),
+            ProblemFormat::ProblemJson
+        );
+    }
+
+    #[test]
+    fn wildcards_match_the_enabled_formats() {
+        let negotiator = Negotiator::new(ProblemFormat::ProblemJson)
+            .formats([ProblemFormat::Html, ProblemFormat::Json]);
+
+        assert_eq!(negotiator.negotiate(Some("*/*")), ProblemFormat::Html);
+        assert_eq!(
+            negotiator.negotiate(Some("application/*;q=0.9, */*;q=0.1")),
+            ProblemFormat::Json
+        );
+        assert_eq!(negotiator.negotiate(Some("TEXT/*")), ProblemFormat::Html);
+    }
+
+    #[test]
+    fn unacceptable_formats_fall_back_to_default() {
+        let negotiator = Negotiator::new(ProblemFormat::Json);
+
+        assert_eq!(negotiator.negotiate(None), ProblemFormat::Json);
+        assert_eq!(negotiator.negotiate(Some(" ")), ProblemFormat::Json);
+        assert_eq!(negotiator.negotiate(Some("image/png")), ProblemFormat::Json);
+        assert_eq!(
+            negotiator.negotiate(Some("text/*;q=0, */*;q=0")),
+            ProblemFormat::Json
+        );
+        assert_eq!(
+            negotiator.negotiate(Some("text/plain;q=2, text/html;q=x, nonsense")),
+            ProblemFormat::Json
+        );
+    }
+
+    #[test]
+    fn rendered_problems_keep_the_status() {
+        let negotiated = Negotiator::default().render(&HttpApiProblem::empty(), Some("text/html"));
+
+        assert_eq!(negotiated.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(negotiated.format, ProblemFormat::Html);
+        assert_eq!(negotiated.content_type(), "text/html; charset=utf-8");
+    }
+
+    fn problem() -> HttpApiProblem {
+        HttpApiProblem::new(StatusCode::NOT_FOUND).detail("missing")
+    }
+
+    fn accept_text() -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::ACCEPT, "text/plain".parse().unwrap());
+        headers
+    }
+
+    const TEXT: &str = "404 Not Found\ndetail: missing\n";
+
+    #[test]
+    fn http_response() {
+        let response = Negotiator::default().to_http_response(&problem(), &accept_text());
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.headers()["content-length"], TEXT.len().to_string());
+        assert_eq!(response.headers()["vary"], "Accept");
+        assert_eq!(response.body(), TEXT);
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn hyper_response() {
+        let response = Negotiator::default().to_hyper_response(&problem(), &accept_text());
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.body(), TEXT);
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn axum_response() {
+        let response = Negotiator::default().to_axum_response(&problem(), &accept_text());
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.headers()["vary"], "Accept");
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[test]
+    fn actix_response() {
+        use crate::actix_web;
+        use actix_web::body::to_bytes;
+        use actix_web::http::header;
+        use actix_web::test::TestRequest;
+
+        actix_web::rt::System::new().block_on(async {
+            let request = TestRequest::default()
+                .insert_header((header::ACCEPT, "text/plain"))
+                .to_http_request();
+
+            let response = Negotiator::default().to_actix_response(&problem(), &request);
+
+            assert_eq!(response.status().as_u16(), 404);
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE).unwrap(),
+                "text/plain; charset=utf-8"
+            );
+            assert_eq!(response.headers().get(header::VARY).unwrap(), "Accept");
+            assert_eq!(to_bytes(response.into_body()).await.unwrap(), TEXT);
+        });
+    }
+
+    #[cfg(feature = "rocket")]
+    struct Negotiated(HttpApiProblem);
+
+    #[cfg(feature = "rocket")]
+    impl<'r> rocket::response::Responder<'r, 'static> for Negotiated {
+        fn respond_to(self, request: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+            Ok(Negotiator::default().to_rocket_response(&self.0, request))
+        }
+    }
+
+    #[cfg(feature = "rocket")]
+    #[rocket::get("/")]
+    fn negotiated() -> Negotiated {
+        Negotiated(problem())
+    }
+
+    #[cfg(feature = "rocket")]
+    #[test]
+    fn rocket_response() {
+        use rocket::http::{Accept, Status};
+        use rocket::local::blocking::Client;
+
+        let client =
+            Client::tracked(rocket::build().mount("/", rocket::routes![negotiated])).unwrap();
+
+        let response = client.get("/").header(Accept::Plain).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(
+            response.headers().get_one("Content-Type"),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept"));
+        assert_eq!(response.into_string().unwrap(), TEXT);
+
+        let response = client.get("/").dispatch();
+        assert_eq!(
+            response.headers().get_one("Content-Type"),
+            Some(crate::PROBLEM_JSON_MEDIA_TYPE)
+        );
+    }
+
+    #[cfg(feature = "salvo")]
+    #[test]
+    fn salvo_response() {
+        let mut request = salvo::Request::new();
+        request
+            .headers_mut()
+            .insert(salvo::http::header::ACCEPT, "text/plain".parse().unwrap());
+
+        let response = Negotiator::default().to_salvo_response(&problem(), &request);
+
+        assert_eq!(response.status_code, Some(StatusCode::NOT_FOUND));
+        assert_eq!(
+            response.headers()[salvo::http::header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.headers()[salvo::http::header::VARY], "Accept");
+    }
+
+    #[cfg(feature = "tide")]
+    #[tokio::test]
+    async fn tide_response() {
+        use tide::http::{Method, Url};
+
+        let mut app = tide::new();
+        app.at("/").get(|request: tide::Request<()>| async move {
+            Ok(Negotiator::default().to_tide_response(&problem(), &request))
+        });
+
+        let mut request =
+            tide::http::Request::new(Method::Get, Url::parse("http://localhost/").unwrap());
+        request.insert_header("Accept", "text/plain");
+        let mut response: tide::http::Response = app.respond(request).await.unwrap();
+
+        assert_eq!(response.status(), 404);
+        assert_eq!(response["Content-Type"], "text/plain;charset=utf-8");
+        assert_eq!(response["Vary"], "Accept");
+        assert_eq!(response.body_string().await.unwrap(), TEXT);
+    }
+
+    #[cfg(feature = "poem")]
+    #[tokio::test]
+    async fn poem_response() {
+        let request = ::poem::Request::builder()
+            .header(http::header::ACCEPT, "text/plain")
+            .finish();
+
+        let response = Negotiator::default().to_poem_response(&problem(), &request);
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.headers()[http::header::VARY], "Accept");
+        assert_eq!(response.into_body().into_string().await.unwrap(), TEXT);
+    }
+}
+
+mod status_type_url {
+    use crate::{HttpApiProblem, StatusTypeUrl};
+    use http::StatusCode;
+
+    #[test]
+    fn base_with_and_without_trailing_slash() {
+        let with_slash = StatusTypeUrl::Base("https://example.com/errors/".to_string());
+        let without_slash = StatusTypeUrl::Base("https://example.com/errors".to_string());
+
+        assert_eq!(
+            with_slash.type_url(StatusCode::CONFLICT).as_deref(),
+            Some("https://example.com/errors/409")
+        );
+        assert_eq!(
+            without_slash.type_url(StatusCode::CONFLICT).as_deref(),
+            Some("https://example.com/errors/409")
+        );
+    }
+
+    #[test]
+    fn omit_keeps_title() {
+        let prob =
+            HttpApiProblem::with_title_and_type_from(StatusCode::CONFLICT, &StatusTypeUrl::Omit);
+
+        assert_eq!(prob.type_url, None);
+        assert_eq!(prob.title.as_deref(), Some("Conflict"));
+    }
+}
+
+mod problem_type {
+    use crate::*;
+
+    const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+        "out-of-credit",
+        "https://example.com/probs/out-of-credit",
+        "You do not have enough credit.",
+        StatusCode::FORBIDDEN,
+    );
+
+    const GONE: ProblemType = ProblemType::new(
+        "gone",
+        "https://example.com/probs/gone",
+        "The resource is gone.",
+        StatusCode::GONE,
+    )
+    .with_documentation_url("https://docs.example.com/gone");
+
+    #[test]
+    fn registering_the_same_type_twice_is_fine() {
+        let registry: ProblemTypeRegistry =
+            [OUT_OF_CREDIT, GONE, OUT_OF_CREDIT].into_iter().collect();
+
+        assert_eq!(2, registry.len());
+        assert_eq!(
+            vec!["out-of-credit", "gone"],
+            registry.iter().map(ProblemType::name).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn conflicting_names_are_rejected() {
+        let mut registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+
+        let result = registry.try_register(ProblemType::new(
+            "out-of-credit",
+            "https://example.com/probs/no-credit",
+            "You do not have enough credit.",
+            StatusCode::FORBIDDEN,
+        ));
+
+        assert_eq!(
+            Err(ProblemTypeError::DuplicateName("out-of-credit")),
+            result
+        );
+        assert_eq!(1, registry.len());
+    }
+
+    #[test]
+    fn invalid_type_urls_are_rejected() {
+        let mut registry = ProblemTypeRegistry::new();
+
+        let result = registry.try_register(ProblemType::new(
+            "out-of-credit",
+            "probs/out of credit",
+            "You do not have enough credit.",
+            StatusCode::FORBIDDEN,
+        ));
+
+        assert_eq!(
+            Err(ProblemTypeError::InvalidTypeUrl("probs/out of credit")),
+            result
+        );
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered for the problem type 'gone'")]
+    fn register_panics_on_conflicting_titles() {
+        let _ = ProblemTypeRegistry::new()
+            .register(GONE)
+            .register(ProblemType::new(
+                "removed",
+                "https://example.com/probs/gone",
+                "The resource was removed.",
+                StatusCode::GONE,
+            ));
+    }
+
+    #[test]
+    fn problems_are_created_by_name() {
+        let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+
+        let problem = registry.problem("out-of-credit").unwrap();
+
+        assert_eq!(Some(StatusCode::FORBIDDEN), problem.status);
+        assert!(OUT_OF_CREDIT.is_type_of(&problem));
+        assert!(registry.problem("unknown").is_none());
+    }
+
+    #[test]
+    fn check_rejects_unregistered_type_urls_and_other_titles() {
+        let registry = ProblemTypeRegistry::new().register(GONE);
+
+        assert!(registry.check(&GONE.problem().detail("user 42")).is_ok());
+        assert!(registry
+            .check(&GONE.problem().status(StatusCode::NOT_FOUND))
+            .is_ok());
+        assert_eq!(
+            Err(ProblemTypeError::TitleMismatch {
+                name: "gone",
+                expected: "The resource is gone.",
+                actual: "Gone".to_string(),
+            }),
+            registry.check(&GONE.problem().title("Gone"))
+        );
+        assert_eq!(
+            Err(ProblemTypeError::UnregisteredTypeUrl(
+                "https://example.com/probs/out-of-credit".to_string()
+            )),
+            registry.check(&OUT_OF_CREDIT.problem())
+        );
+        assert!(registry
+            .check(&HttpApiProblem::about_blank(StatusCode::GONE))
+            .is_ok());
+    }
+
+    #[test]
+    fn documentation_url_defaults_to_type_url() {
+        assert_eq!(OUT_OF_CREDIT.type_url(), OUT_OF_CREDIT.documentation_url());
+        assert_eq!("https://docs.example.com/gone", GONE.documentation_url());
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn api_errors_are_created_by_name() {
+        let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+
+        let error = registry
+            .api_error_builder("out-of-credit")
+            .unwrap()
+            .message("Your current balance is 30, but that costs 50.")
+            .finish();
+        let problem = error.into_http_api_problem();
+
+        assert_eq!(
+            Some("https://example.com/probs/out-of-credit"),
+            problem.type_url.as_deref()
+        );
+        assert_eq!(
+            Some("You do not have enough credit."),
+            problem.title.as_deref()
+        );
+        assert!(registry.check(&problem).is_ok());
+    }
+}
+
+mod problem_type_docs {
+    use crate::*;
+
+    const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+        "out-of-credit",
+        "https://example.com/probs/out-of-credit",
+        "You do not have enough credit.",
+        StatusCode::FORBIDDEN,
+    )
+    .with_description("The balance is lower than the price of the <purchase>.")
+    .with_extension_members(&[
+        ExtensionMember::new("balance", "integer", "The current balance").with_example("30"),
+        ExtensionMember::new("currency", "string", "ISO 4217 code").with_example("EUR"),
+    ]);
+
+    #[test]
+    fn markdown_page_documents_the_problem_type() {
+        let page = ProblemTypeDocs::new(DocsFormat::Markdown).page(&OUT_OF_CREDIT);
+
+        assert!(page.starts_with("# You do not have enough credit.\n"));
+        assert!(page.contains("- **status**: 403 Forbidden\n"));
+        assert!(page.contains("| `balance` | integer | The current balance |\n"));
+        assert!(page.contains("\"balance\": 30"));
+        assert!(page.contains("\"currency\": \"EUR\""));
+        assert!(!page.contains("documentation"));
+    }
+
+    #[test]
+    fn markdown_page_is_escaped() {
+        const ESCAPED: ProblemType = ProblemType::new(
+            "escaped",
+            "https://example.com/probs/a|b",
+            "Either | or",
+            StatusCode::CONFLICT,
+        )
+        .with_documentation_url("https://example.com/docs/<escaped>")
+        .with_extension_members(&[ExtensionMember::new("`quoted`", "string", "The name")]);
+
+        let page = ProblemTypeDocs::new(DocsFormat::Markdown).page(&ESCAPED);
+
+        assert!(page.starts_with("# Either \\| or\n"));
+        assert!(page.contains("- **type**: `https://example.com/probs/a|b`\n"));
+        assert!(page.contains("- **documentation**: `https://example.com/docs/<escaped>`\n"));
+        assert!(page.contains("| `` `quoted` `` | string | The name |\n"));
+    }
+
+    #[test]
+    fn markdown_text_is_escaped() {
+        const SCRIPT: ProblemType = ProblemType::new(
+            "script",
+            "https://example.com/probs/script",
+            "<script>alert(1)</script> [x](javascript:alert(1))",
+            StatusCode::CONFLICT,
+        )
+        .with_description("# *not* a heading\n& <b>bold</b>");
+
+        let page = ProblemTypeDocs::new(DocsFormat::Markdown).page(&SCRIPT);
+
+        assert!(page
+            .starts_with("# &lt;script\\>alert(1)&lt;/script\\> \\[x\\](javascript:alert(1))\n"));
+        assert!(page.contains("\n\\# \\*not\\* a heading &amp; &lt;b\\>bold&lt;/b\\>\n"));
+    }
+
+    #[test]
+    fn only_http_and_relative_documentation_urls_are_links() {
+        const SCRIPT: ProblemType = ProblemType::new(
+            "script",
+            "https://example.com/probs/script",
+            "Script",
+            StatusCode::CONFLICT,
+        )
+        .with_documentation_url("javascript:alert(1)");
+        const RELATIVE: ProblemType = SCRIPT.with_documentation_url("/docs/script");
+
+        let html = ProblemTypeDocs::new(DocsFormat::Html);
+        let markdown = ProblemTypeDocs::new(DocsFormat::Markdown);
+
+        assert!(html
+            .page(&SCRIPT)
+            .contains("<dt>documentation</dt><dd><code>javascript:alert(1)</code></dd>"));
+        assert!(markdown
+            .page(&SCRIPT)
+            .contains("- **documentation**: `javascript:alert(1)`\n"));
+        assert!(html
+            .page(&RELATIVE)
+            .contains("<a href=\"/docs/script\">/docs/script</a>"));
+        assert!(markdown
+            .page(&RELATIVE)
+            .contains("- **documentation**: [`/docs/script`](</docs/script>)\n"));
+    }
+
+    #[test]
+    fn html_page_is_escaped() {
+        let page = ProblemTypeDocs::new(DocsFormat::Html).page(&OUT_OF_CREDIT);
+
+        assert!(page.contains("<h1>You do not have enough credit.</h1>"));
+        assert!(
+            page.contains("<p>The balance is lower than the price of the &lt;purchase&gt;.</p>")
+        );
+        assert!(page.contains("&quot;balance&quot;: 30"));
+    }
+
+    #[test]
+    fn index_links_to_pages() {
+        let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+        let index = ProblemTypeDocs::new(DocsFormat::Html)
+            .title("Shop API")
+            .index(&registry);
+
+        assert!(index.contains("<h1>Shop API</h1>"));
+        assert!(index.contains("<a href=\"out-of-credit.html\">You do not have enough credit.</a>"));
+    }
+
+    #[test]
+    fn file_names_are_sanitized() {
+        let docs = ProblemTypeDocs::new(DocsFormat::Html);
+        let file_name =
+            |name| docs.file_name(&ProblemType::new(name, "/probs", "", StatusCode::GONE));
+
+        assert_eq!(file_name("out-of-credit_2"), "out-of-credit_2.html");
+        assert_eq!(file_name("../../etc/passwd"), "______etc_passwd.html");
+        assert_eq!(file_name("..\\secret"), "___secret.html");
+        assert_eq!(file_name("/abs"), "_abs.html");
+        assert_eq!(file_name("ü ber"), "__ber.html");
+        assert_eq!(file_name(""), "_.html");
+    }
+
+    #[test]
+    fn write_stays_in_the_directory() {
+        let registry = ProblemTypeRegistry::new().register(ProblemType::new(
+            "../escaped",
+            "/probs/escaped",
+            "Escaped",
+            StatusCode::GONE,
+        ));
+        let dir =
+            std::env::temp_dir().join(format!("problem-type-docs-esc-{}", std::process::id()));
+        let docs = dir.join("docs");
+
+        ProblemTypeDocs::new(DocsFormat::Markdown)
+            .write(&registry, &docs)
+            .unwrap();
+
+        assert!(docs.join("___escaped.md").exists());
+        assert!(!dir.join("escaped.md").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_fails_for_pages_with_the_same_file_name() {
+        let dir =
+            std::env::temp_dir().join(format!("problem-type-docs-dup-{}", std::process::id()));
+
+        for [first, second] in [["index", "other"], ["a.b", "a_b"], ["Gone", "gone"]] {
+            let registry = ProblemTypeRegistry::new()
+                .register(ProblemType::new(
+                    first,
+                    "/probs/first",
+                    "First",
+                    StatusCode::GONE,
+                ))
+                .register(ProblemType::new(
+                    second,
+                    "/probs/second",
+                    "Second",
+                    StatusCode::GONE,
+                ));
+
+            let err = ProblemTypeDocs::new(DocsFormat::Markdown)
+                .write(&registry, &dir)
+                .unwrap_err();
+
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+            assert!(!dir.exists());
+        }
+    }
+
+    #[test]
+    fn write_creates_a_file_per_page() {
+        let registry = ProblemTypeRegistry::new().register(OUT_OF_CREDIT);
+        let dir = std::env::temp_dir().join(format!("problem-type-docs-{}", std::process::id()));
+
+        ProblemTypeDocs::new(DocsFormat::Markdown)
+            .write(&registry, &dir)
+            .unwrap();
+
+        let page = std::fs::read_to_string(dir.join("out-of-credit.md")).unwrap();
+        assert_eq!(
+            ProblemTypeDocs::new(DocsFormat::Markdown).page(&OUT_OF_CREDIT),
+            page
+        );
+        assert!(dir.join("index.md").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+mod problem_type_docs_handler {
+    use crate::*;
+    use http::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+
+    const OUT_OF_CREDIT: ProblemType = ProblemType::new(
+        "out-of-credit",
+        "https://example.com/probs/out-of-credit",
+        "You do not have enough credit.",
+        StatusCode::FORBIDDEN,
+    )
+    .with_extension_members(&[ExtensionMember::new(
+        "balance",
+        "integer",
+        "The current balance",
+    )
+    .with_example("30")]);
+
+    const NOT_PAID: ProblemType = ProblemType::new(
+        "not-paid",
+        "probs/not-paid",
+        "The order has not been paid.",
+        StatusCode::CONFLICT,
+    );
+
+    fn handler() -> ProblemTypeDocsHandler {
+        ProblemTypeDocsHandler::new(
+            ProblemTypeRegistry::new()
+                .register(OUT_OF_CREDIT)
+                .register(NOT_PAID),
+        )
+    }
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn paths_of_absolute_and_relative_type_urls() {
+        assert_eq!(
+            vec!["/probs/out-of-credit", "/probs/not-paid"],
+            handler().paths()
+        );
+    }
+
+    #[test]
+    fn browsers_get_html() {
+        let response = handler().handle(
+            "/probs/out-of-credit",
+            &accept("text/html,application/xhtml+xml,*/*;q=0.8"),
+        );
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("text/html; charset=utf-8", response.headers()[CONTENT_TYPE]);
+        assert!(response
+            .body()
+            .contains("<h1>You do not have enough credit.</h1>"));
+    }
+
+    #[test]
+    fn machines_get_json() {
+        let response = handler().handle("/probs/out-of-credit", &accept("application/json"));
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("application/json", response.headers()[CONTENT_TYPE]);
+
+        let json: serde_json::Value = serde_json::from_str(response.body()).unwrap();
+        assert_eq!(json["type"], "https://example.com/probs/out-of-credit");
+        assert_eq!(json["status"], 403);
+        assert_eq!(json["extension-members"][0]["name"], "balance");
+        assert_eq!(json["example"]["balance"], 30);
+    }
+
+    #[test]
+    fn unknown_paths_are_not_found() {
+        let response = handler().handle("/probs/unknown", &HeaderMap::new());
+
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        assert_eq!(PROBLEM_JSON_MEDIA_TYPE, response.headers()[CONTENT_TYPE]);
+    }
+
+    #[cfg(feature = "axum-extract")]
+    #[tokio::test]
+    async fn axum_router_serves_the_docs() {
+        use axum_core::body::Body;
+        use tower_service::Service;
+
+        let mut router: axum_crate::Router = handler().into_axum_router();
+
+        let request = http::Request::get("/probs/not-paid")
+            .header(ACCEPT, "text/html")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.call(request).await.unwrap();
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = axum_crate::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("<h1>The order has not been paid.</h1>"));
+    }
+
+    #[cfg(any(feature = "axum-extract", feature = "actix-web"))]
+    fn handler_with_special_paths() -> ProblemTypeDocsHandler {
+        ProblemTypeDocsHandler::new(
+            ["/probs/:id", "/probs/*rest", "/probs/gone"]
+                .into_iter()
+                .enumerate()
+                .map(|(idx, type_url)| {
+                    let name: &'static str = Box::leak(format!("type-{}", idx).into_boxed_str());
+                    ProblemType::new(name, type_url, name, StatusCode::GONE)
+                })
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "axum-extract")]
+    #[tokio::test]
+    async fn axum_router_serves_paths_with_special_characters() {
+        use axum_core::body::Body;
+        use tower_service::Service;
+
+        let mut router: axum_crate::Router = handler_with_special_paths().into_axum_router();
+
+        for (path, expected) in [
+            ("/probs/:id", Some("type-0")),
+            ("/probs/*rest", Some("type-1")),
+            ("/probs/gone", Some("type-2")),
+            ("/probs/42", None),
+            ("/probs/a/b", None),
+        ] {
+            let request = http::Request::get(path).body(Body::empty()).unwrap();
+            let response = router.call(request).await.unwrap();
+
+            let status = response.status();
+            let body = axum_crate::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            match expected {
+                Some(name) => {
+                    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    assert_eq!(json["name"], name, "{}", path);
+                }
+                None => assert_eq!(StatusCode::NOT_FOUND, status, "{}", path),
+            }
+        }
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[test]
+    fn actix_app_serves_paths_with_special_characters() {
+        use actix_web::test::{call_service, init_service, TestRequest};
+
+        actix_web::rt::System::new().block_on(async {
+            let handler = handler_with_special_paths();
+            let app = init_service(
+                actix_web::App::new().configure(|config| handler.configure_actix(config)),
+            )
+            .await;
+
+            for (path, expected) in [
+                ("/probs/:id", 200),
+                ("/probs/*rest", 200),
+                ("/probs/gone", 200),
+                ("/probs/42", 404),
+            ] {
+                let request = TestRequest::get().uri(path).to_request();
+                let response = call_service(&app, request).await;
+
+                assert_eq!(expected, response.status().as_u16(), "{}", path);
+            }
+        });
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[test]
+    fn actix_app_serves_the_docs() {
+        use actix_web::test::{call_service, init_service, read_body, TestRequest};
+
+        actix_web::rt::System::new().block_on(async {
+            let handler = handler();
+            let app = init_service(
+                actix_web::App::new().configure(|config| handler.configure_actix(config)),
+            )
+            .await;
+
+            let request = TestRequest::get()
+                .uri("/probs/out-of-credit")
+                .insert_header(("accept", "application/json"))
+                .to_request();
+            let response = call_service(&app, request).await;
+
+            assert_eq!(200, response.status().as_u16());
+            assert_eq!(
+                "application/json",
+                response.headers().get("content-type").unwrap()
+            );
+            let json: serde_json::Value =
+                serde_json::from_slice(&read_body(response).await).unwrap();
+            assert_eq!(json["name"], "out-of-credit");
+        });
+    }
+}
+
+mod extension_members {
+    use crate::{HttpApiProblem, Negotiator, ProblemFormat};
+    use http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct OutOfCredit {
+        balance: u32,
+    }
+
+    #[test]
+    fn typed_members_are_flattened() {
+        let prob = HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .value("untyped", &true)
+            .with_extension_members(OutOfCredit { balance: 30 });
+
+        let sample = serde_json::to_value(prob).unwrap();
+        let expected = json!({
+            "status": 403,
+            "balance": 30,
+            "untyped": true
+        });
+
+        assert_eq!(sample, expected);
+    }
+
+    #[test]
+    fn deserialization_errors_of_typed_members_are_reported() {
+        let json = r#"{"status": 403, "balance": "thirty"}"#;
+
+        let result = serde_json::from_str::<HttpApiProblem<OutOfCredit>>(json);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn untyped_round_trip() {
+        let typed = HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .value("untyped", &true)
+            .with_extension_members(OutOfCredit { balance: 30 });
+
+        let untyped = typed.clone().into_untyped().unwrap();
+        assert_eq!(untyped.get_value::<String, u32>("balance"), Some(30));
+
+        let typed_again = untyped.into_typed::<OutOfCredit>().unwrap();
+        assert_eq!(typed_again, typed);
+    }
+
+    #[test]
+    fn typed_members_replace_untyped_members_with_the_same_name() {
+        let prob = HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .value("balance", &"thirty")
+            .with_extension_members(OutOfCredit { balance: 30 });
+
+        let json = prob.json_string();
+
+        assert_eq!(1, json.matches("\"balance\"").count());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            json!({"status": 403, "balance": 30})
+        );
+    }
+
+    #[test]
+    fn typed_members_named_like_standard_members_are_left_out() {
+        #[derive(Serialize)]
+        struct Members {
+            status: u16,
+            title: String,
+            balance: u32,
+        }
+
+        let prob = HttpApiProblem::new(StatusCode::FORBIDDEN).with_extension_members(Members {
+            status: 200,
+            title: "typed".to_string(),
+            balance: 30,
+        });
+
+        let json = prob.json_string();
+
+        assert_eq!(1, json.matches("\"status\"").count());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            json!({"status": 403, "balance": 30})
+        );
+    }
+
+    #[test]
+    fn typed_members_have_to_be_a_map() {
+        let prob = HttpApiProblem::new(StatusCode::FORBIDDEN).with_extension_members(30);
+
+        assert!(serde_json::to_string(&prob).is_err());
+        assert!(prob.try_json_bytes().is_err());
+        assert!(prob.try_json_string().is_err());
+        assert!(prob.extension_members_json().is_err());
+        assert!(prob.try_render(ProblemFormat::Text).is_err());
+    }
+
+    #[test]
+    fn unserializable_problems_are_rendered_as_internal_server_errors() {
+        let prob = HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .detail("secret")
+            .with_extension_members(vec![1, 2]);
+
+        let response = Negotiator::default().to_http_response(&prob, &http::HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body: HttpApiProblem = serde_json::from_str(response.body()).unwrap();
+        assert_eq!(
+            body,
+            HttpApiProblem::with_title_and_type(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+
+        let negotiated = Negotiator::default().render(&prob, Some("text/html"));
+        assert_eq!(negotiated.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!negotiated.body.contains("secret"));
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn unserializable_problems_respond_with_internal_server_errors() {
+        let response = HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .with_extension_members(30)
+            .to_axum_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
+
+mod compliance {
+    use crate::{uri, ComplianceViolation, HttpApiProblem, ValidationProblem};
+    use http::StatusCode;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[test]
+    fn uri_references() {
+        for valid in [
+            "",
+            "about:blank",
+            "https://example.com/probs/out-of-credit",
+            "https://[::1]:8080/probs?a=b#c",
+            "/account/12345/msgs/abc",
+            "probs/not-paid",
+            "./a:b",
+            "?query",
+            "#fragment",
+            "/caf%C3%A9",
+            "urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+        ] {
+            assert!(uri::is_uri_reference(valid), "{:?} is valid", valid);
+        }
+
+        for invalid in [
+            "probs/out of credit",
+            ":b/c",
+            "1probs:x",
+            "/caf%C3%A",
+            "/caf%zz",
+            "/café",
+            "/a#b#c",
+            "/a[b]",
+            "<https://example.com>",
+        ] {
+            assert!(!uri::is_uri_reference(invalid), "{:?} is invalid", invalid);
+        }
+    }
+
+    #[test]
+    fn valid_problem() {
+        let problem = ValidationProblem::new()
+            .pointer("/name", "must not be empty")
+            .into_http_api_problem()
+            .type_url("https://example.com/probs/validation")
+            .instance("/account/12345")
+            .value("trace_id", &"abc");
+
+        assert_eq!(Ok(()), problem.validate());
+        assert!(HttpApiProblem::empty().validate().is_ok());
+    }
+
+    #[test]
+    fn reports_all_violations() {
+        let mut problem = HttpApiProblem::new(StatusCode::MOVED_PERMANENTLY)
+            .type_url("not a uri")
+            .instance("/a b")
+            .value("ok", &1)
+            .value("1st", &1)
+            .value("with-dash", &1);
+        problem
+            .additional_fields_mut()
+            .insert("title".to_string(), json!("Duplicate"));
+
+        let err = problem.validate().unwrap_err();
+
+        assert_eq!(
+            err.violations(),
+            &[
+                ComplianceViolation::InvalidTypeUrl("not a uri".to_string()),
+                ComplianceViolation::StatusOutOfRange(StatusCode::MOVED_PERMANENTLY),
+                ComplianceViolation::InvalidInstance("/a b".to_string()),
+                ComplianceViolation::InvalidExtensionName("1st".to_string()),
+                ComplianceViolation::InvalidExtensionName("ok".to_string()),
+                ComplianceViolation::ReservedExtensionName("title".to_string()),
+                ComplianceViolation::InvalidExtensionName("with-dash".to_string()),
+            ]
+        );
+        assert!(err.to_string().starts_with(
+            "the problem does not comply with RFC 9457: 'type' is not a URI reference"
+        ));
+    }
+
+    #[test]
+    fn reports_reserved_typed_extension_members() {
+        #[derive(Serialize)]
+        struct Members {
+            status: u16,
+        }
+
+        let problem = HttpApiProblem::new(StatusCode::NOT_FOUND)
+            .with_extension_members(Members { status: 200 });
+
+        assert_eq!(
+            problem.validate().unwrap_err().violations(),
+            &[ComplianceViolation::ReservedExtensionName(
+                "status".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn compares_with_response_status() {
+        let problem = HttpApiProblem::new(StatusCode::NOT_FOUND);
+
+        assert!(problem
+            .validate_response_status(StatusCode::NOT_FOUND)
+            .is_ok());
+        assert!(HttpApiProblem::empty()
+            .validate_response_status(StatusCode::NOT_FOUND)
+            .is_ok());
+        assert_eq!(
+            problem
+                .validate_response_status(StatusCode::GONE)
+                .unwrap_err()
+                .violations(),
+            &[ComplianceViolation::StatusMismatch {
+                problem: StatusCode::NOT_FOUND,
+                response: StatusCode::GONE,
+            }]
+        );
+    }
+
+    #[test]
+    fn standard_members_in_additional_fields_are_not_serialized() {
+        let mut problem = HttpApiProblem::new(StatusCode::NOT_FOUND);
+        problem
+            .additional_fields_mut()
+            .insert("status".to_string(), json!(200));
+        problem
+            .additional_fields_mut()
+            .insert("balance".to_string(), json!(30));
+
+        let json = problem.json_string();
+
+        assert_eq!(1, json.matches("\"status\"").count());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            json!({"status": 404, "balance": 30})
+        );
+        assert!(!problem
+            .extension_members_json()
+            .unwrap()
+            .contains_key("status"));
+    }
+
+    #[test]
+    fn strict_parsing_accepts_compliant_problems() {
+        let json = r#"{
+            "type": "https://example.com/probs/out-of-credit",
+            "title": "You do not have enough credit.",
+            "status": 403,
+            "instance": "/account/12345/msgs/abc",
+            "balance": 30
+        }"#;
+
+        let problem = HttpApiProblem::from_json_str_strict(json).unwrap();
+
+        assert_eq!(problem.status, Some(StatusCode::FORBIDDEN));
+        assert_eq!(problem.get_value::<&str, u32>("balance"), Some(30));
+    }
+
+    #[test]
+    fn strict_parsing_reports_invalid_status() {
+        let lenient: HttpApiProblem = serde_json::from_str(r#"{"status": 1000}"#).unwrap();
+        assert_eq!(lenient.status, None);
+
+        for status in [json!(1000), json!(42), json!("404"), json!(404.5)] {
+            let json = json!({ "status": status, "title": "Oops" }).to_string();
+
+            let err = HttpApiProblem::from_json_str_strict(&json).unwrap_err();
+            assert_eq!(
+                err.violations().first(),
+                Some(&ComplianceViolation::InvalidStatus(status.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn strict_parsing_reports_violations() {
+        let json = r#"{"type": "a b", "status": 200, "x": 1}"#;
+
+        let err = HttpApiProblem::from_json_slice_strict(json.as_bytes()).unwrap_err();
+
+        assert_eq!(
+            err.into_violations(),
+            vec![
+                ComplianceViolation::InvalidTypeUrl("a b".to_string()),
+                ComplianceViolation::StatusOutOfRange(StatusCode::OK),
+                ComplianceViolation::InvalidExtensionName("x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_parsing_reports_invalid_documents() {
+        for json in ["[]", "{", r#"{"title": 5}"#] {
+            let err = HttpApiProblem::from_json_str_strict(json).unwrap_err();
+
+            assert!(
+                matches!(err.violations(), [ComplianceViolation::InvalidDocument(_)]),
+                "{:?}",
+                err
+            );
+        }
+    }
+}
+
+mod validation {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_pointer_escapes_segments() {
+        assert_eq!(json_pointer(["a~b", "c/d", "0"]), "/a~0b/c~1d/0");
+    }
+
+    #[test]
+    fn name_is_derived_from_pointer() {
+        let param = InvalidParam::at_pointer(json_pointer(["a~b", "c/d", "0"]), "invalid");
+
+        assert_eq!(param.name, "a~b.c/d.0");
+        assert_eq!(param.pointer.as_deref(), Some("/a~0b/c~1d/0"));
+    }
+
+    #[test]
+    fn renders_invalid_params_extension() {
+        let problem: HttpApiProblem = ValidationProblem::new()
+            .pointer("/age", "must be a positive integer")
+            .query("color", "must be 'green', 'red' or 'blue'")
+            .into();
+
+        let value = serde_json::to_value(&problem).unwrap();
+
+        assert_eq!(value["status"], json!(422));
+        assert_eq!(value["title"], json!("Unprocessable Entity"));
+        assert_eq!(
+            value["invalid-params"],
+            json!([
+                {"name": "age", "pointer": "/age", "reason": "must be a positive integer"},
+                {"name": "color", "reason": "must be 'green', 'red' or 'blue'"}
+            ])
+        );
+    }
+
+    #[test]
+    fn overrides_derived_members() {
+        let problem = ValidationProblem::new()
+            .status(StatusCode::BAD_REQUEST)
+            .title("Your request parameters didn't validate.")
+            .type_url("https://example.net/validation-error")
+            .detail("2 parameters are invalid")
+            .instance("/requests/1")
+            .into_http_api_problem();
+
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            problem.title.as_deref(),
+            Some("Your request parameters didn't validate.")
+        );
+        assert_eq!(
+            problem.type_url.as_deref(),
+            Some("https://example.net/validation-error")
+        );
+        assert_eq!(problem.detail.as_deref(), Some("2 parameters are invalid"));
+        assert_eq!(problem.instance.as_deref(), Some("/requests/1"));
+        assert_eq!(problem.invalid_params().unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn leaves_out_empty_invalid_params() {
+        let problem = ValidationProblem::new()
+            .status(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+            .into_http_api_problem();
+
+        let value = serde_json::to_value(&problem).unwrap();
+
+        assert_eq!(value.get("invalid-params"), None);
+        assert_eq!(problem.invalid_params().unwrap(), Vec::new());
+
+        let typed = HttpApiProblem::with_title_and_type(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+            .with_extension_members(InvalidParams::default());
+
+        assert_eq!(serde_json::to_value(&typed).unwrap(), value);
+    }
+
+    #[test]
+    fn parses_invalid_params() {
+        let json = r#"{
+            "status": 400,
+            "invalid-params": [
+                {"name": "age", "pointer": "/age", "reason": "must be a positive integer", "code": "range"}
+            ]
+        }"#;
+
+        let problem: HttpApiProblem = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            problem.invalid_params().unwrap(),
+            vec![InvalidParam::at_pointer("/age", "must be a positive integer").code("range")]
+        );
+
+        let typed: HttpApiProblem<InvalidParams> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            typed.extension_members.invalid_params,
+            problem.invalid_params().unwrap()
+        );
+    }
+
+    #[test]
+    fn typed_invalid_params() {
+        let expected = vec![InvalidParam::at_pointer("/age", "must be positive")];
+        let typed =
+            HttpApiProblem::new(StatusCode::BAD_REQUEST).with_extension_members(InvalidParams {
+                invalid_params: expected.clone(),
+            });
+
+        assert_eq!(typed.invalid_params().unwrap(), expected);
+
+        let converted = HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .value(INVALID_PARAMS_KEY, &expected)
+            .into_typed::<InvalidParams>()
+            .unwrap();
+
+        assert_eq!(converted.invalid_params().unwrap(), expected);
+    }
+
+    #[test]
+    fn no_invalid_params_is_empty() {
+        assert!(HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .invalid_params()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn malformed_invalid_params_are_an_error() {
+        let problem =
+            HttpApiProblem::new(StatusCode::BAD_REQUEST).value(INVALID_PARAMS_KEY, &"oops");
+
+        assert!(problem.invalid_params().is_err());
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn api_error_round_trip() {
+        let validation = ValidationProblem::new().pointer("/items/0", "must not be empty");
+        let expected = validation.invalid_params().to_vec();
+
+        let error: ApiError = validation.into();
+
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.invalid_params().unwrap(), expected);
+        assert_eq!(
+            error.into_http_api_problem().invalid_params().unwrap(),
+            expected
+        );
+
+        let error: ApiError = ValidationProblem::new().into();
+        assert!(error.fields().is_empty());
+    }
+}
+
+#[cfg(feature = "validator")]
+mod validator {
+    use crate::*;
+    use ::validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+
+    fn errors() -> ValidationErrors {
+        let mut address = ValidationErrors::new();
+        address.add(
+            "zip",
+            ValidationError::new("length").with_message(Cow::from("must have 5 digits")),
+        );
+
+        let mut item = ValidationErrors::new();
+        item.add("name", ValidationError::new("required"));
+
+        let mut errors = ValidationErrors::new();
+        errors.add(
+            "age",
+            ValidationError::new("range").with_message(Cow::from("must be positive")),
+        );
+        errors.add("__all__", ValidationError::new("schema"));
+
+        errors.errors_mut().insert(
+            "items",
+            ValidationErrorsKind::List(BTreeMap::from([(1, Box::new(item))])),
+        );
+
+        ValidationErrors::merge(Err(errors), "address", Err(address)).unwrap_err()
+    }
+
+    #[test]
+    fn flattens_nested_errors() {
+        let problem = ValidationProblem::from(errors());
+
+        let pointers: Vec<_> = problem
+            .invalid_params()
+            .iter()
+            .map(|p| (p.pointer.as_deref().unwrap(), p.code.as_deref().unwrap()))
+            .collect();
+
+        assert_eq!(
+            pointers,
+            vec![
+                ("", "schema"),
+                ("/address/zip", "length"),
+                ("/age", "range"),
+                ("/items/1/name", "required"),
+            ]
+        );
+        assert_eq!(problem.invalid_params()[1].name, "address.zip");
+        assert_eq!(problem.invalid_params()[1].reason, "must have 5 digits");
+    }
+
+    #[test]
+    fn converts_into_http_api_problem() {
+        let problem: HttpApiProblem = errors().into();
+
+        assert_eq!(problem.status, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(problem.invalid_params().unwrap().len(), 4);
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn converts_into_api_error() {
+        let error: ApiError = errors().into();
+
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.invalid_params().unwrap().len(), 4);
+    }
+}
+
+#[cfg(feature = "garde")]
+mod garde {
+    use crate::*;
+    use ::garde::error::{NoKey, Path};
+    use ::garde::{Error, Report};
+
+    fn report() -> Report {
+        let mut report = Report::new();
+        report.append(Path::new("age"), Error::new("must be positive"));
+        report.append(
+            Path::new("items").join(1).join("a/b"),
+            Error::new("must not be empty"),
+        );
+        report.append(
+            Path::new("nickname").join(NoKey::default()),
+            Error::new("too long"),
+        );
+        report
+    }
+
+    #[test]
+    fn flattens_nested_errors() {
+        let problem = ValidationProblem::from(report());
+
+        assert_eq!(
+            problem.invalid_params(),
+            &[
+                InvalidParam::at_pointer("/age", "must be positive"),
+                InvalidParam::at_pointer("/items/1/a~1b", "must not be empty"),
+                InvalidParam::at_pointer("/nickname", "too long"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_and_list_paths() {
+        let mut report = Report::new();
+        report.append(
+            Path::new("address").join("street"),
+            Error::new("must not be empty"),
+        );
+        report.append(Path::new(0).join("name"), Error::new("too short"));
+        report.append(
+            Path::new("matrix").join(0).join(2),
+            Error::new("out of range"),
+        );
+        report.append(
+            Path::new("tags")
+                .join(NoKey::default())
+                .join(3)
+                .join(NoKey::default()),
+            Error::new("invalid tag"),
+        );
+        report.append(
+            Path::new("orders")
+                .join(1)
+                .join("items")
+                .join(0)
+                .join("sku"),
+            Error::new("unknown sku"),
+        );
+
+        let pointers: Vec<_> = ValidationProblem::from(report)
+            .invalid_params()
+            .iter()
+            .map(|param| param.pointer.clone().unwrap())
+            .collect();
+
+        assert_eq!(
+            pointers,
+            [
+                "/address/street",
+                "/0/name",
+                "/matrix/0/2",
+                "/tags/3",
+                "/orders/1/items/0/sku",
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_with_separators_stay_one_segment() {
+        let mut report = Report::new();
+        report.append(
+            Path::new("labels").join("app.kubernetes.io/name"),
+            Error::new("invalid label"),
+        );
+        report.append(
+            Path::new("scores").join("a[0]").join(2),
+            Error::new("out of range"),
+        );
+
+        let pointers: Vec<_> = ValidationProblem::from(report)
+            .invalid_params()
+            .iter()
+            .map(|param| param.pointer.clone().unwrap())
+            .collect();
+
+        assert_eq!(
+            pointers,
+            ["/labels/app.kubernetes.io~1name", "/scores/a[0]/2"]
+        );
+    }
+
+    #[test]
+    fn converts_into_http_api_problem() {
+        let problem = HttpApiProblem::from(report());
+
+        assert_eq!(problem.status, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(problem.invalid_params().unwrap()[1].name, "items.1.a/b");
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn converts_into_api_error() {
+        let error = ApiError::from(report());
+
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.invalid_params().unwrap().len(), 3);
+    }
+}
+
+#[cfg(feature = "tower")]
+mod problem_layer {
+    use crate::*;
+    use bytes::Bytes;
+    use http::{header, Method, Request, Response};
+    use http_body_util::{BodyExt, Full};
+    use std::convert::Infallible;
+    use std::future::{ready, Ready};
+    use std::task::{Context, Poll};
+    use tower_layer::Layer;
+    use tower_service::Service;
+
+    #[derive(Clone)]
+    struct Respond(Response<Full<Bytes>>);
+
+    impl Service<Request<()>> for Respond {
+        type Response = Response<Full<Bytes>>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Request<()>) -> Self::Future {
+            ready(Ok(self.0.clone()))
+        }
+    }
+
+    fn response(
+        status: StatusCode,
+        content_type: Option<&str>,
+        body: &str,
+    ) -> Response<Full<Bytes>> {
+        let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+        *response.status_mut() = status;
+        if let Some(content_type) = content_type {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        }
+        response
+    }
+
+    async fn call(
+        layer: ProblemLayer,
+        response: Response<Full<Bytes>>,
+    ) -> (http::response::Parts, Bytes) {
+        call_with(layer, Method::GET, response).await
+    }
+
+    async fn call_with(
+        layer: ProblemLayer,
+        method: Method,
+        response: Response<Full<Bytes>>,
+    ) -> (http::response::Parts, Bytes) {
+        let request = Request::builder().method(method).body(()).unwrap();
+        let response = layer.layer(Respond(response)).call(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        (parts, body.collect().await.unwrap().to_bytes())
+    }
+
+    #[tokio::test]
+    async fn replaces_error_bodies() {
+        let mut original = response(StatusCode::METHOD_NOT_ALLOWED, None, "");
+        original
+            .headers_mut()
+            .insert(header::ALLOW, "GET".parse().unwrap());
+
+        let (parts, body) = call(ProblemLayer::new(), original).await;
+
+        assert_eq!(parts.status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(parts.headers[header::CONTENT_TYPE], PROBLEM_JSON_MEDIA_TYPE);
+        assert_eq!(parts.headers[header::ALLOW], "GET");
+        assert_eq!(
+            parts.headers[header::CONTENT_LENGTH],
+            body.len().to_string().as_str()
+        );
+
+        let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            problem,
+            HttpApiProblem::with_title_and_type(StatusCode::METHOD_NOT_ALLOWED)
+        );
+    }
+
+    #[tokio::test]
+    async fn uses_the_body_as_detail() {
+        let original = response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Some("text/plain; charset=utf-8"),
+            "length limit exceeded\n",
+        );
+
+        let (_, body) = call(ProblemLayer::new().detail_from_body(true), original).await;
+
+        let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.detail.as_deref(), Some("length limit exceeded"));
+    }
+
+    #[tokio::test]
+    async fn ignores_the_body_by_default() {
+        let original = response(StatusCode::NOT_FOUND, None, "nothing here");
+
+        let (_, body) = call(ProblemLayer::new(), original).await;
+
+        let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.detail, None);
+    }
+
+    #[tokio::test]
+    async fn ignores_the_body_of_server_errors() {
+        let original = response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("text/plain"),
+            "connection to db-01 refused",
+        );
+
+        let (_, body) = call(ProblemLayer::new().detail_from_body(true), original).await;
+
+        let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.detail, None);
+    }
+
+    #[tokio::test]
+    async fn head_responses_have_no_body() {
+        let mut original = response(StatusCode::NOT_FOUND, Some("text/plain"), "");
+        original
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, "9".parse().unwrap());
+
+        let (parts, body) = call_with(
+            ProblemLayer::new().detail_from_body(true),
+            Method::HEAD,
+            original,
+        )
+        .await;
+
+        assert_eq!(parts.status, StatusCode::NOT_FOUND);
+        assert_eq!(parts.headers[header::CONTENT_TYPE], PROBLEM_JSON_MEDIA_TYPE);
+        assert_eq!(parts.headers.get(header::CONTENT_LENGTH), None);
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ignores_non_text_bodies() {
+        let original = response(
+            StatusCode::NOT_FOUND,
+            Some("text/html"),
+            "<h1>Not Found</h1>",
+        );
+
+        let (_, body) = call(ProblemLayer::new().detail_from_body(true), original).await;
+
+        let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.detail, None);
+    }
+
+    #[tokio::test]
+    async fn keeps_problems_and_successes() {
+        for (status, content_type) in [
+            (StatusCode::CONFLICT, Some("application/problem+json")),
+            (StatusCode::OK, None),
+            (StatusCode::PERMANENT_REDIRECT, Some("text/plain")),
+        ] {
+            let (parts, body) = call(
+                ProblemLayer::new(),
+                response(status, content_type, "original"),
+            )
+            .await;
+
+            assert_eq!(parts.status, status);
+            assert_eq!(body, "original");
+        }
+    }
+}
+
+#[cfg(feature = "api-error")]
+mod derive_into_api_error {
+    use crate::*;
+    use serde_json::json;
+    use std::error::Error;
+    use std::io;
+
+    #[derive(IntoApiError)]
+    #[api_error(
+        status = 409,
+        title = "Conflict",
+        message = "account {account} of {owner:?} is locked",
+        type_url = "/probs/locked",
+        instance = "/accounts/{account}"
+    )]
+    struct Locked {
+        #[api_error(field)]
+        account: u32,
+        #[api_error(field = "owner_name")]
+        owner: String,
+    }
+
+    #[derive(IntoApiError)]
+    #[api_error(status = 400, message = "{1} {0:>5} {{not a field}} {0:#x}")]
+    struct Positional(u32, &'static str);
+
+    #[derive(IntoApiError)]
+    #[api_error(title = "Unavailable")]
+    struct Unavailable;
+
+    #[derive(IntoApiError)]
+    #[api_error(status = StatusCode::BAD_GATEWAY, message = "upstream failed: {cause}")]
+    struct Upstream {
+        #[api_error(source)]
+        cause: io::Error,
+    }
+
+    #[derive(IntoApiError)]
+    enum AccountError {
+        #[api_error(status = 404, message = "account {0} not found")]
+        NotFound(u32),
+        #[api_error(status = StatusCode::SERVICE_UNAVAILABLE)]
+        Storage(#[source] io::Error),
+        #[api_error(status = 422, message = "limit {limit} exceeded")]
+        Limit {
+            #[api_error(field)]
+            limit: u64,
+        },
+        Locked(Locked),
+    }
+
+    #[test]
+    fn struct_with_named_fields() {
+        let error: ApiError = Locked {
+            account: 12,
+            owner: "ada".to_string(),
+        }
+        .into();
+
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        assert_eq!(error.title(), Some("Conflict"));
+        assert_eq!(error.message(), Some("account 12 of \"ada\" is locked"));
+        assert_eq!(error.type_url(), Some("/probs/locked"));
+        assert_eq!(error.instance(), Some("/accounts/12"));
+        assert_eq!(error.fields().get("account"), Some(&json!(12)));
+        assert_eq!(error.fields().get("owner_name"), Some(&json!("ada")));
+        assert_eq!(error.fields().len(), 2);
+    }
+
+    #[test]
+    fn struct_with_positional_fields() {
+        let error: ApiError = Positional(42, "answer").into();
+
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error.message(), Some("answer    42 {not a field} 0x2a"));
+        assert!(error.fields().is_empty());
+    }
+
+    #[test]
+    fn unit_struct_defaults_to_internal_server_error() {
+        let error: ApiError = Unavailable.into();
+
+        assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.title(), Some("Unavailable"));
+        assert_eq!(error.message(), None);
+    }
+
+    #[test]
+    fn source_field() {
+        let cause = io::Error::other("connection reset");
+        let error: ApiError = Upstream { cause }.into();
+
+        assert_eq!(error.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(error.message(), Some("upstream failed: connection reset"));
+        assert_eq!(error.source().unwrap().to_string(), "connection reset");
+    }
+
+    #[test]
+    fn enum_variants() {
+        let error: ApiError = AccountError::NotFound(7).into();
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error.message(), Some("account 7 not found"));
+
+        let cause = io::Error::other("disk on fire");
+        let error: ApiError = AccountError::Storage(cause).into();
+        assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.source().unwrap().to_string(), "disk on fire");
+
+        let error: ApiError = AccountError::Limit { limit: 100 }.into();
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.message(), Some("limit 100 exceeded"));
+        assert_eq!(error.fields().get("limit"), Some(&json!(100)));
+
+        let error: ApiError = AccountError::Locked(Locked {
+            account: 3,
+            owner: "bob".to_string(),
+        })
+        .into();
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        assert_eq!(error.instance(), Some("/accounts/3"));
+    }
+
+    #[derive(Debug, serde::Serialize)]
+    struct Timeout(&'static str);
+
+    impl std::fmt::Display for Timeout {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl Error for Timeout {}
+
+    #[derive(IntoApiError)]
+    #[api_error(status = 500, message = "{user}: {cause}")]
+    struct Renamed {
+        #[api_error(source, field = "reason")]
+        cause: Timeout,
+        #[api_error(field = "user_id")]
+        user: u32,
+    }
+
+    #[test]
+    fn source_which_is_also_a_field() {
+        let error: ApiError = Renamed {
+            cause: Timeout("timeout"),
+            user: 5,
+        }
+        .into();
+
+        assert_eq!(error.message(), Some("5: timeout"));
+        assert_eq!(error.fields().get("reason"), Some(&json!("timeout")));
+        assert_eq!(error.fields().get("user_id"), Some(&json!(5)));
+        assert_eq!(error.source().unwrap().to_string(), "timeout");
+    }
+
+    #[derive(IntoApiError)]
+    #[api_error(status = 400, message = "invalid value {0}")]
+    struct Invalid<T: std::fmt::Display>(T);
+
+    #[test]
+    fn generic_struct() {
+        let error: ApiError = Invalid(1.5).into();
+
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error.message(), Some("invalid value 1.5"));
+    }
+}
+
+#[cfg(feature = "catch-panic")]
+mod catch_panic {
+    use crate::*;
+
+    #[test]
+    fn from_panic_keeps_details_out_of_the_problem() {
+        install_panic_hook();
+        let payload = std::panic::catch_unwind(|| panic!("secret {}", 42)).unwrap_err();
+
+        let error = ApiError::from_panic(payload);
+
+        let details = PanicDetails::of(&error).unwrap().clone();
+        assert_eq!(details.message.as_deref(), Some("secret 42"));
+        assert!(details.location.unwrap().contains("test.rs"));
+
+        let instance = error.instance().unwrap().to_string();
+        let problem = error.into_http_api_problem();
+        assert_eq!(problem.status, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(problem.instance.as_deref(), Some(instance.as_str()));
+        assert_eq!(instance.len(), "urn:uuid:".len() + 36);
+        assert!(!problem.json_string().contains("secret"));
+    }
+
+    #[test]
+    fn from_panic_ignores_details_of_other_panics() {
+        install_panic_hook();
+        let payload = std::panic::catch_unwind(|| panic!("first {}", 1)).unwrap_err();
+        let _ = std::panic::catch_unwind(|| panic!("second {}", 2));
+
+        let error = ApiError::from_panic(payload);
+
+        let details = PanicDetails::of(&error).unwrap();
+        assert_eq!(details.message.as_deref(), Some("first 1"));
+        assert_eq!(details.location, None);
+    }
+
+    #[test]
+    fn from_panic_has_no_location_for_payloads_of_other_threads() {
+        install_panic_hook();
+        let payload = std::thread::spawn(|| panic!("in thread {}", 1))
+            .join()
+            .unwrap_err();
+        let _ = std::panic::catch_unwind(|| panic!("stale"));
+
+        let error = ApiError::from_panic(payload);
+
+        let details = PanicDetails::of(&error).unwrap();
+        assert_eq!(details.message.as_deref(), Some("in thread 1"));
+        assert_eq!(details.location, None);
+    }
+
+    #[cfg(feature = "tower")]
+    mod tower {
+        use crate::*;
+        use bytes::Bytes;
+        use http::{header, Request, Response};
+        use http_body_util::{BodyExt, Full};
+        use std::convert::Infallible;
+        use std::future::{ready, Ready};
+        use std::sync::{Arc, Mutex};
+        use std::task::{Context, Poll};
+        use tower_layer::Layer;
+        use tower_service::Service;
+
+        #[derive(Clone)]
+        struct Handler;
+
+        impl Service<Request<()>> for Handler {
+            type Response = Response<Full<Bytes>>;
+            type Error = Infallible;
+            type Future = Ready<Result<Self::Response, Infallible>>;
+
+            fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, req: Request<()>) -> Self::Future {
+                if req.uri() == "/panic" {
+                    panic!("handler panicked");
+                }
+                ready(Ok(Response::new(Full::new(Bytes::from("ok")))))
+            }
+        }
+
+        #[tokio::test]
+        async fn responds_with_a_problem() {
+            let caught = Arc::new(Mutex::new(None));
+            let on_panic = Arc::clone(&caught);
+            let mut service = CatchPanicLayer::new()
+                .on_panic(move |err| {
+                    *on_panic.lock().unwrap() = PanicDetails::of(err).cloned();
+                })
+                .layer(Handler);
+
+            let request = Request::builder().uri("/panic").body(()).unwrap();
+            let response = service.call(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                response.headers()[header::CONTENT_TYPE],
+                PROBLEM_JSON_MEDIA_TYPE
+            );
+            let details = response.extensions().get::<PanicDetails>().cloned();
+            assert_eq!(details, *caught.lock().unwrap());
+            assert_eq!(
+                details.unwrap().message.as_deref(),
+                Some("handler panicked")
+            );
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+            assert!(problem.instance.unwrap().starts_with("urn:uuid:"));
+            assert_eq!(problem.detail, None);
+        }
+
+        #[tokio::test]
+        async fn passes_responses_through() {
+            let mut service = CatchPanicLayer::new().layer(Handler);
+
+            let request = Request::builder().uri("/").body(()).unwrap();
+            let response = service.call(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body, "ok");
+        }
+    }
+
+    #[cfg(feature = "actix-web")]
+    mod actix {
+        use crate::*;
+        use actix_web::body::to_bytes;
+        use actix_web::test::{init_service, try_call_service, TestRequest};
+        use actix_web::{web, App, HttpResponse};
+
+        async fn panicking() -> HttpResponse {
+            panic!("handler panicked")
+        }
+
+        #[test]
+        fn responds_with_a_problem() {
+            actix_web::rt::System::new().block_on(async {
+                let app = init_service(
+                    App::new()
+                        .wrap(CatchPanic::new())
+                        .route("/panic", web::get().to(panicking)),
+                )
+                .await;
+
+                let request = TestRequest::get().uri("/panic").to_request();
+                let error = try_call_service(&app, request).await.unwrap_err();
+                let response = error.error_response();
+
+                assert_eq!(response.status().as_u16(), 500);
+                let details = response.extensions().get::<PanicDetails>().cloned();
+                assert_eq!(
+                    details.unwrap().message.as_deref(),
+                    Some("handler panicked")
+                );
+
+                let body = to_bytes(response.into_body()).await.unwrap();
+                let problem: HttpApiProblem = serde_json::from_slice(&body).unwrap();
+                assert!(problem.instance.unwrap().starts_with("urn:uuid:"));
+            });
+        }
+    }
+}
+
+#[cfg(feature = "axum-extract")]
+mod axum_extract {
+    use crate::*;
+    use axum_core::body::Body;
+    use axum_core::extract::{FromRequest, FromRequestParts, Request};
+    use axum_crate::extract::{Form, Json, Query};
+    use axum_extra::headers::UserAgent;
+    use axum_extra::TypedHeader;
+    use http::header;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Order {
+        items: Vec<Item>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        quantity: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Page {
+        page: u32,
+    }
+
+    fn json_request(body: &str) -> Request {
+        Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn json_data_errors_point_to_the_member() {
+        let request = json_request(r#"{"items": [{"quantity": 1}, {"quantity": -1}]}"#);
+
+        let problem = Problem::<Json<Order>>::from_request(request, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert!(problem.detail.is_some());
+        let params = problem.invalid_params().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].pointer.as_deref(), Some("/items/1/quantity"));
+        assert_eq!(params[0].name, "items.1.quantity");
+    }
+
+    #[tokio::test]
+    async fn json_syntax_errors_keep_the_status() {
+        let rejection = Json::<Order>::from_request(json_request("{"), &())
+            .await
+            .unwrap_err();
+
+        let problem = HttpApiProblem::from(rejection);
+
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(problem.json_value(INVALID_PARAMS_KEY), None);
+    }
+
+    #[tokio::test]
+    async fn missing_content_type() {
+        let request = Request::builder().body(Body::from("{}")).unwrap();
+
+        let problem = Problem::<Json<Order>>::from_request(request, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct OrderItem {
+        order: u32,
+        item: u32,
+    }
+
+    async fn path_problem(uri: &str) -> HttpApiProblem {
+        use axum_crate::extract::Path;
+        use axum_crate::routing::get;
+        use axum_crate::Router;
+        use tower_service::Service;
+
+        let mut router: Router = Router::new()
+            .route(
+                "/orders/:order/items/:item",
+                get(|Problem(Path(ids)): Problem<Path<OrderItem>>| async move {
+                    format!("{}/{}", ids.order, ids.item)
+                }),
+            )
+            .route(
+                "/customers/:customer/orders/:order",
+                get(|Problem(Path(ids)): Problem<Path<(u32, u32)>>| async move {
+                    format!("{}/{}", ids.0, ids.1)
+                }),
+            );
+
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = router.call(request).await.unwrap();
+        let body = axum_crate::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn path_errors_point_to_the_parameter() {
+        let problem = path_problem("/orders/1/items/x").await;
+
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        let params = problem.invalid_params().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].pointer.as_deref(), Some("/item"));
+        assert_eq!(params[0].name, "item");
+
+        let problem = path_problem("/customers/1/orders/x").await;
+
+        let params = problem.invalid_params().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].pointer.as_deref(), Some("/1"));
+        assert_eq!(params[0].name, "1");
+    }
+
+    #[tokio::test]
+    async fn server_errors_have_no_detail() {
+        use axum_crate::extract::Path;
+
+        let (mut parts, _) = Request::builder()
+            .uri("/orders/1")
+            .body(Body::empty())
+            .unwrap()
+            .into_parts();
+
+        let problem = Problem::<Path<u32>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(problem.detail, None);
+    }
+
+    #[tokio::test]
+    async fn query_names_missing_fields() {
+        let (mut parts, _) = Request::builder()
+            .uri("/?size=10")
+            .body(Body::empty())
+            .unwrap()
+            .into_parts();
+
+        let problem = Problem::<Query<Page>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            problem.invalid_params().unwrap(),
+            vec![InvalidParam::new("page", "missing field `page`")]
+        );
+    }
+
+    #[tokio::test]
+    async fn form_names_missing_fields() {
+        let request = Request::builder()
+            .method("POST")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("size=10"))
+            .unwrap();
+
+        let problem = Problem::<Form<Page>>::from_request(request, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(problem.invalid_params().unwrap()[0].name, "page");
+    }
+
+    #[tokio::test]
+    async fn typed_header_names_the_header() {
+        let (mut parts, _) = Request::builder().body(Body::empty()).unwrap().into_parts();
+
+        let problem = Problem::<TypedHeader<UserAgent>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            serde_json::to_value(problem.invalid_params().unwrap()).unwrap(),
+            json!([{"name": "user-agent", "reason": "missing"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_the_inner_value() {
+        let Problem(Json(order)) =
+            Problem::<Json<Order>>::from_request(json_request(r#"{"items": []}"#), &())
+                .await
+                .unwrap();
+
+        assert!(order.items.is_empty());
+    }
+
+    #[cfg(feature = "api-error")]
+    #[tokio::test]
+    async fn converts_into_api_error() {
+        let rejection = Json::<Order>::from_request(json_request(r#"{"items": 1}"#), &())
+            .await
+            .unwrap_err();
+
+        let error = ApiError::from(rejection);
+
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error.invalid_params().unwrap()[0].pointer.as_deref(),
+            Some("/items")
+        );
+    }
+}
+
+#[cfg(feature = "actix-web")]
+mod actix_errors {
+    use crate::*;
+    use actix_web::body::{to_bytes, MessageBody};
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::header;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Page {
+        page: u32,
+    }
+
+    async fn handler(_: web::Query<Page>) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn json_handler(_: web::Json<Page>) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn form_handler(_: web::Form<Page>) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn path_handler(_: web::Path<u32>) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn problem<B: MessageBody>(response: ServiceResponse<B>) -> HttpApiProblem {
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+        let body = to_bytes(response.into_body()).await.ok().unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn run<F: std::future::Future>(f: F) -> F::Output {
+        actix_web::rt::System::new().block_on(f)
+    }
+
+    #[test]
+    fn error_handlers_respond_with_problems() {
+        run(async {
+            let app = init_service(
+                App::new()
+                    .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+                    .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+                    .app_data(web::FormConfig::default().error_handler(form_error_handler))
+                    .app_data(web::PathConfig::default().error_handler(path_error_handler))
+                    .route("/query", web::get().to(handler))
+                    .route("/json", web::post().to(json_handler))
+                    .route("/form", web::post().to(form_handler))
+                    .route("/path/{id}", web::get().to(path_handler)),
+            )
+            .await;
+
+            let request = TestRequest::get().uri("/query?size=1").to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+            assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+            let request = TestRequest::post()
+                .uri("/json")
+                .insert_header((header::CONTENT_TYPE, "application/json"))
+                .set_payload("{}")
+                .to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+            assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+            let request = TestRequest::post()
+                .uri("/form")
+                .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+                .set_payload("size=1")
+                .to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+            assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+            let request = TestRequest::get().uri("/path/abc").to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+            assert!(p.detail.is_some());
+        });
+    }
+
+    #[test]
+    fn error_handlers_middleware_converts_error_responses() {
+        run(async {
+            let app = init_service(
+                App::new()
+                    .wrap(problem_error_handlers())
+                    .route("/query", web::get().to(handler))
+                    .route(
+                        "/problem",
+                        web::get().to(|| async {
+                            HttpApiProblem::new(StatusCode::CONFLICT).to_actix_response()
+                        }),
+                    )
+                    .route(
+                        "/teapot",
+                        web::get().to(|| async {
+                            HttpResponse::ImATeapot()
+                                .insert_header(("x-tea", "earl grey"))
+                                .body("short and stout")
+                        }),
+                    ),
+            )
+            .await;
+
+            let request = TestRequest::get().uri("/missing").to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(
+                p,
+                HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)
+            );
+
+            let request = TestRequest::get().uri("/query").to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+            assert!(p.detail.unwrap().contains("page"));
+
+            let request = TestRequest::get().uri("/teapot").to_request();
+            let response = call_service(&app, request).await;
+            assert_eq!(response.headers().get("x-tea").unwrap(), "earl grey");
+            let p = problem(response).await;
+            assert_eq!(p.status, Some(StatusCode::IM_A_TEAPOT));
+            assert_eq!(p.detail, None);
+
+            let request = TestRequest::get().uri("/problem").to_request();
+            let p = problem(call_service(&app, request).await).await;
+            assert_eq!(p, HttpApiProblem::new(StatusCode::CONFLICT));
+        });
+    }
+
+    #[test]
+    fn server_errors_have_no_detail() {
+        let err = serde_json::from_str::<u32>("secret").unwrap_err();
+
+        let p = HttpApiProblem::from(actix_web::error::JsonPayloadError::Serialize(err));
+
+        assert_eq!(p.status, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(p.detail, None);
+    }
+}
+
+#[cfg(feature = "warp")]
+mod warp_recover {
+    use crate::*;
+    use serde::Deserialize;
+    use warp::http::header;
+    use warp::Filter;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Page {
+        page: u32,
+    }
+
+    fn problem(response: warp::http::Response<warp::hyper::body::Bytes>) -> HttpApiProblem {
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    fn routes(
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+        let conflict = warp::path("conflict").and_then(|| async {
+            Err::<String, _>(warp::reject::custom(HttpApiProblem::new(
+                StatusCode::CONFLICT,
+            )))
+        });
+        let header = warp::path("header")
+            .and(warp::header::<u32>("x-page"))
+            .map(|page: u32| page.to_string());
+        let body = warp::path("body")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(16))
+            .and(warp::body::json())
+            .map(|_: Page| "ok");
+
+        conflict.or(header).or(body).recover(recover)
+    }
+
+    #[tokio::test]
+    async fn recover_keeps_rejected_problems() {
+        let response = warp::test::request()
+            .path("/conflict")
+            .reply(&routes())
+            .await;
+
+        assert_eq!(response.status().as_u16(), 409);
+        assert_eq!(problem(response), HttpApiProblem::new(StatusCode::CONFLICT));
+    }
+
+    #[tokio::test]
+    async fn recover_maps_not_found() {
+        let response = warp::test::request().path("/nope").reply(&routes()).await;
+
+        assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(
+            problem(response),
+            HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[tokio::test]
+    async fn recover_maps_header_rejections() {
+        let response = warp::test::request().path("/header").reply(&routes()).await;
+        let p = problem(response);
+        assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(p.invalid_params().unwrap()[0].name, "x-page");
+        assert_eq!(p.invalid_params().unwrap()[0].reason, "missing");
+
+        let response = warp::test::request()
+            .path("/header")
+            .header("x-page", "abc")
+            .reply(&routes())
+            .await;
+        let p = problem(response);
+        assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(p.invalid_params().unwrap()[0].reason, "invalid");
+    }
+
+    #[tokio::test]
+    async fn recover_maps_body_rejections() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/body")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body("{}")
+            .reply(&routes())
+            .await;
+        let p = problem(response);
+        assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/body")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(r#"{"page": 1, "padding": "................"}"#)
+            .reply(&routes())
+            .await;
+        assert_eq!(response.status().as_u16(), 413);
+        assert_eq!(
+            problem(response).status,
+            Some(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+    }
+
+    #[tokio::test]
+    async fn recover_maps_method_not_allowed() {
+        let response = warp::test::request()
+            .method("GET")
+            .path("/body")
+            .reply(&routes())
+            .await;
+
+        assert_eq!(response.status().as_u16(), 405);
+    }
+
+    #[cfg(feature = "api-error")]
+    #[tokio::test]
+    async fn recover_keeps_rejected_api_errors() {
+        let routes = warp::any()
+            .and_then(|| async {
+                Err::<String, _>(warp::reject::custom(ApiError::new(StatusCode::FORBIDDEN)))
+            })
+            .recover(recover);
+
+        let response = warp::test::request().reply(&routes).await;
+
+        assert_eq!(response.status().as_u16(), 403);
+        assert_eq!(problem(response).status, Some(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn problems_are_warp_replies() {
+        use warp::Reply;
+
+        let response = HttpApiProblem::new(StatusCode::IM_A_TEAPOT).into_response();
+
+        assert_eq!(response.status().as_u16(), 418);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+    }
+}
+
+#[cfg(feature = "rocket")]
+mod rocket_catchers {
+    use crate::*;
+    use rocket::form::{Errors, Form};
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    #[allow(dead_code)]
+    #[derive(rocket::FromForm)]
+    struct User<'r> {
+        #[field(validate = len(3..))]
+        name: &'r str,
+        age: u8,
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[rocket::post("/users", data = "<user>")]
+    fn create_user(user: Result<Form<User<'_>>, Errors<'_>>) -> Result<Status, HttpApiProblem> {
+        user.map_err(HttpApiProblem::from)?;
+        Ok(Status::Created)
+    }
+
+    #[rocket::get("/fail")]
+    fn fail() -> Status {
+        Status::InternalServerError
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .mount("/", rocket::routes![create_user, fail])
+            .register("/", problem_catchers());
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn problem(response: rocket::local::blocking::LocalResponse<'_>) -> HttpApiProblem {
+        assert_eq!(
+            response.headers().get_one("Content-Type"),
+            Some(PROBLEM_JSON_MEDIA_TYPE)
+        );
+        serde_json::from_slice(&response.into_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn catchers_respond_with_problems() {
+        let client = client();
+
+        let response = client.get("/missing").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(
+            problem(response),
+            HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND).instance("/missing")
+        );
+
+        let response = client.get("/fail").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+        assert_eq!(
+            problem(response).status,
+            Some(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[test]
+    fn form_errors_become_validation_problems() {
+        let client = client();
+
+        let response = client
+            .post("/users")
+            .header(ContentType::Form)
+            .body("name=ab")
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let p = problem(response);
+        let params = p.invalid_params().unwrap();
+        assert_eq!(p.status, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(params.len(), 2);
+        assert!(params.iter().any(|p| p.name == "name"));
+        assert!(params
+            .iter()
+            .any(|p| p.name == "age" && p.reason == "missing"));
+
+        let response = client
+            .post("/users")
+            .header(ContentType::Form)
+            .body("name=abc&age=3")
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+    }
+
+    #[cfg(feature = "api-error")]
+    #[allow(clippy::result_large_err)]
+    #[rocket::get("/forbidden")]
+    fn forbidden() -> Result<(), ApiError> {
+        Err(ApiError::new(StatusCode::FORBIDDEN))
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn api_error_is_a_responder() {
+        let rocket = rocket::build().mount("/", rocket::routes![forbidden]);
+        let client = Client::tracked(rocket).unwrap();
+
+        let response = client.get("/forbidden").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(problem(response).status, Some(StatusCode::FORBIDDEN));
+    }
+}
+
+#[cfg(feature = "poem")]
+mod poem {
+    use crate::*;
+    use ::poem::http::header;
+    use ::poem::{get, handler, Endpoint, Request, Response, Route};
+
+    #[allow(clippy::result_large_err)]
+    #[handler]
+    fn conflict() -> Result<String, HttpApiProblem> {
+        Err(HttpApiProblem::new(StatusCode::CONFLICT).detail("already exists"))
+    }
+
+    async fn problem(response: Response) -> HttpApiProblem {
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+        let body = response.into_body().into_bytes().await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn problem_is_a_response_error() {
+        let app = Route::new().at("/", get(conflict));
+
+        let response = app.get_response(Request::builder().finish()).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            problem(response).await,
+            HttpApiProblem::new(StatusCode::CONFLICT).detail("already exists")
+        );
+    }
+
+    #[test]
+    fn poem_errors_become_problems() {
+        let problem = HttpApiProblem::from(::poem::Error::from_status(StatusCode::NOT_FOUND));
+        assert_eq!(problem.status, Some(StatusCode::NOT_FOUND));
+        assert!(problem.detail.is_some());
+
+        let problem =
+            HttpApiProblem::from(::poem::Error::from_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(
+            problem,
+            HttpApiProblem::with_title_and_type(StatusCode::SERVICE_UNAVAILABLE)
+        );
+
+        let original = HttpApiProblem::new(StatusCode::GONE).title("Gone for good");
+        let problem = HttpApiProblem::from(::poem::Error::from(original.clone()));
+        assert_eq!(problem, original);
+    }
+
+    #[cfg(feature = "api-error")]
+    #[tokio::test]
+    async fn api_error_is_a_response_error() {
+        #[allow(clippy::result_large_err)]
+        #[handler]
+        fn forbidden() -> Result<String, ApiError> {
+            Err(ApiError::new(StatusCode::FORBIDDEN))
+        }
+
+        let app = Route::new().at("/", get(forbidden));
+        let response = app.get_response(Request::builder().finish()).await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(problem(response).await.status, Some(StatusCode::FORBIDDEN));
+
+        let error = ApiError::from(::poem::Error::from(
+            ApiError::builder(StatusCode::CONFLICT)
+                .message("taken")
+                .finish(),
+        ));
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        assert_eq!(error.message(), Some("taken"));
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn poem_errors_become_api_errors() {
+        let error = ApiError::from(::poem::Error::from_status(StatusCode::NOT_FOUND));
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert!(error.detail_message().is_some());
+
+        let error = ApiError::from(::poem::Error::from_string(
+            "secret",
+            StatusCode::SERVICE_UNAVAILABLE,
+        ));
+        assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.detail_message(), None);
+        assert_eq!(
+            error.into_http_api_problem(),
+            HttpApiProblem::with_title_and_type(StatusCode::SERVICE_UNAVAILABLE)
+        );
+
+        let original = HttpApiProblem::new(StatusCode::GONE)
+            .type_url("/probs/gone")
+            .title("Gone for good")
+            .detail("moved away")
+            .value("since", &2024);
+        let error = ApiError::from(::poem::Error::from(original.clone()));
+        assert_eq!(error.into_http_api_problem(), original);
+    }
+
+    #[cfg(feature = "poem-openapi")]
+    #[tokio::test]
+    async fn problems_are_api_responses() {
+        use ::poem_openapi::param::Path;
+        use ::poem_openapi::payload::PlainText;
+        use ::poem_openapi::{OpenApi, OpenApiService};
+
+        struct Api;
+
+        #[OpenApi]
+        impl Api {
+            #[oai(path = "/users/:id", method = "get")]
+            async fn user(&self, id: Path<u32>) -> Result<PlainText<String>, HttpApiProblem> {
+                match id.0 {
+                    0 => Err(HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)),
+                    id => Ok(PlainText(id.to_string())),
+                }
+            }
+        }
+
+        let service = OpenApiService::new(Api, "Users", "1.0");
+        let spec = service.spec();
+        assert!(spec.contains(PROBLEM_JSON_MEDIA_TYPE));
+        assert!(spec.contains("#/components/schemas/HttpApiProblem"));
+
+        let app = Route::new().nest("/", service);
+        let response = app
+            .get_response(Request::builder().uri_str("/users/0").finish())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            problem(response).await,
+            HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)
+        );
+    }
+}
+
+#[cfg(feature = "ntex-tokio")]
+mod ntex {
+    use crate::*;
+    use ::ntex::http::header;
+    use ::ntex::web::error::{JsonPayloadError, QueryPayloadError};
+    use ::ntex::web::test::{call_service, init_service, read_body, TestRequest};
+    use ::ntex::web::{self, App, HttpResponse, WebResponse};
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Page {
+        page: u32,
+    }
+
+    #[allow(clippy::result_large_err)]
+    async fn query_handler(
+        page: Result<web::types::Query<Page>, QueryPayloadError>,
+    ) -> Result<HttpResponse, HttpApiProblem> {
+        page.map_err(HttpApiProblem::from)?;
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    #[allow(clippy::result_large_err)]
+    async fn json_handler(
+        page: Result<web::types::Json<Page>, JsonPayloadError>,
+    ) -> Result<HttpResponse, HttpApiProblem> {
+        page.map_err(HttpApiProblem::from)?;
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    async fn problem(response: WebResponse) -> HttpApiProblem {
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+        serde_json::from_slice(&read_body(response).await).unwrap()
+    }
+
+    #[::ntex::test]
+    async fn extractor_errors_become_problems() {
+        let app = init_service(
+            App::new()
+                .route("/query", web::get().to(query_handler))
+                .route("/json", web::post().to(json_handler)),
+        )
+        .await;
+
+        let request = TestRequest::get().uri("/query?size=1").to_request();
+        let p = problem(call_service(&app, request).await).await;
+        assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+        let request = TestRequest::post()
+            .uri("/json")
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload("{}")
+            .to_request();
+        let p = problem(call_service(&app, request).await).await;
+        assert_eq!(p.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(p.invalid_params().unwrap()[0].name, "page");
+
+        let request = TestRequest::get().uri("/query?page=1").to_request();
+        assert_eq!(call_service(&app, request).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn web_errors_become_problems() {
+        let error = web::Error::from(JsonPayloadError::Overflow);
+        let p = HttpApiProblem::from(error);
+        assert_eq!(p.status, Some(StatusCode::PAYLOAD_TOO_LARGE));
+        assert!(p.detail.is_some());
+
+        let error = web::Error::from(std::io::Error::other("disk on fire"));
+        assert_eq!(
+            HttpApiProblem::from(error),
+            HttpApiProblem::with_title_and_type(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[cfg(feature = "api-error")]
+    #[::ntex::test]
+    async fn api_error_is_a_web_response_error() {
+        #[allow(clippy::result_large_err)]
+        async fn forbidden() -> Result<HttpResponse, ApiError> {
+            Err(ApiError::new(StatusCode::FORBIDDEN))
+        }
+
+        let app = init_service(App::new().route("/", web::get().to(forbidden))).await;
+
+        let response = call_service(&app, TestRequest::get().uri("/").to_request()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(problem(response).await.status, Some(StatusCode::FORBIDDEN));
+    }
+}
+
+#[cfg(feature = "tonic")]
+mod tonic_status {
+    use crate::*;
+    use tonic_types::{ErrorDetails, StatusExt};
+
+    #[test]
+    fn problem_travels_in_the_status_details() {
+        let problem = ValidationProblem::new()
+            .detail("invalid user")
+            .pointer("/name", "too short")
+            .into_http_api_problem()
+            .value("trace", &"abc");
+
+        let status = problem.to_tonic_status();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(status.message(), "invalid user");
+
+        let details = status.get_error_details();
+        let info = details.error_info().unwrap();
+        assert_eq!(info.domain, PROBLEM_ERROR_INFO_DOMAIN);
+        assert_eq!(info.reason, "UNPROCESSABLE_ENTITY");
+        let violations = &details.bad_request().unwrap().field_violations;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "name");
+        assert_eq!(violations[0].description, "too short");
+        assert!(!info.metadata["problem"].contains('\n'));
+
+        assert_eq!(HttpApiProblem::from(status), problem);
+    }
+
+    #[test]
+    fn foreign_status_becomes_a_problem() {
+        let status = tonic::Status::not_found("no such user");
+        assert_eq!(
+            HttpApiProblem::from(status),
+            HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND).detail("no such user")
+        );
+
+        let status = tonic::Status::with_error_details(
+            tonic::Code::InvalidArgument,
+            "",
+            ErrorDetails::with_bad_request_violation("email", "not an email"),
+        );
+        let problem = HttpApiProblem::from(status);
+        assert_eq!(problem.status, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(problem.detail, None);
+        assert_eq!(
+            problem.invalid_params().unwrap(),
+            vec![InvalidParam::new("email", "not an email")]
+        );
+    }
+
+    #[test]
+    fn status_codes_are_mapped_in_both_directions() {
+        let codes = [
+            tonic::Code::InvalidArgument,
+            tonic::Code::NotFound,
+            tonic::Code::PermissionDenied,
+            tonic::Code::Unauthenticated,
+            tonic::Code::ResourceExhausted,
+            tonic::Code::Unimplemented,
+            tonic::Code::Internal,
+            tonic::Code::Unavailable,
+            tonic::Code::DeadlineExceeded,
+            tonic::Code::Cancelled,
+        ];
+        for code in codes {
+            assert_eq!(grpc_code_for_status(status_for_grpc_code(code)), code);
+        }
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn api_error_converts_to_and_from_status() {
+        let error = ApiError::builder(StatusCode::CONFLICT)
+            .message("already taken")
+            .field("user", "joe")
+            .finish();
+
+        let status = tonic::Status::from(error);
+        assert_eq!(status.code(), tonic::Code::Aborted);
+
+        let error = ApiError::from(status);
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        assert_eq!(error.message(), Some("already taken"));
+        assert_eq!(error.fields().get("user").unwrap(), "joe");
+        assert!(error.source().is_some());
+    }
+}
+
+#[cfg(feature = "utoipa")]
+mod utoipa_schema {
+    use crate::*;
+    use utoipa::OpenApi;
+
+    #[utoipa::path(get, path = "/users/{id}", responses((status = 200, description = "The user"), HttpApiProblem))]
+    #[allow(dead_code)]
+    fn get_user() {}
+
+    #[derive(OpenApi)]
+    #[openapi(paths(get_user), components(schemas(HttpApiProblem)))]
+    struct ApiDoc;
+
+    #[test]
+    fn problem_is_documented_as_default_response() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let schema = &spec["components"]["schemas"]["HttpApiProblem"];
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["status"]["type"], "integer");
+
+        let default = &spec["paths"]["/users/{id}"]["get"]["responses"]["default"];
+        assert_eq!(
+            default["content"][PROBLEM_JSON_MEDIA_TYPE]["schema"]["$ref"],
+            "#/components/schemas/HttpApiProblem"
+        );
+    }
+
+    #[derive(utoipa::ToSchema)]
+    #[allow(dead_code)]
+    struct OutOfCredit {
+        balance: u32,
+        accounts: Option<Vec<String>>,
+    }
+
+    #[test]
+    fn typed_extension_members_are_added_to_the_schema() {
+        use utoipa::{PartialSchema, ToSchema};
+
+        assert_eq!(HttpApiProblem::<()>::name(), "HttpApiProblem");
+        assert_eq!(
+            HttpApiProblem::<OutOfCredit>::name(),
+            "HttpApiProblem_for_OutOfCredit"
+        );
+
+        let schema = serde_json::to_value(HttpApiProblem::<OutOfCredit>::schema()).unwrap();
+
+        assert_eq!(schema["properties"]["type"]["type"], "string");
+        assert_eq!(schema["properties"]["balance"]["type"], "integer");
+        assert_eq!(schema["properties"]["accounts"]["type"][0], "array");
+        assert_eq!(schema["required"], serde_json::json!(["balance"]));
+    }
+
+    #[test]
+    fn problem_responses_have_examples_per_status() {
+        let responses = ProblemResponses::new()
+            .problem(&HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND))
+            .problem(&HttpApiProblem::new(StatusCode::CONFLICT).title("Taken"))
+            .problem(&HttpApiProblem::new(StatusCode::CONFLICT).title("Taken"));
+        let responses = serde_json::to_value(utoipa::openapi::Responses::from(responses)).unwrap();
+
+        assert_eq!(responses["404"]["description"], "Not Found");
+        let content = &responses["409"]["content"][PROBLEM_JSON_MEDIA_TYPE];
+        assert_eq!(content["examples"]["Taken"]["value"]["status"], 409);
+        assert_eq!(content["examples"]["Taken (2)"]["value"]["title"], "Taken");
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn problem_responses_take_api_errors() {
+        let error = ApiError::builder(StatusCode::FORBIDDEN)
+            .title("Not your user")
+            .field("user", 42)
+            .finish();
+        let responses = ProblemResponses::new().error(&error).into_responses();
+        let response = serde_json::to_value(&responses["403"]).unwrap();
+
+        let example = &response["content"][PROBLEM_JSON_MEDIA_TYPE]["examples"]["Not your user"];
+        assert_eq!(example["value"]["user"], 42);
+    }
+}
+
+#[cfg(feature = "aide")]
+mod aide_output {
+    use crate::*;
+    use aide::gen::in_context;
+    use aide::openapi::Operation;
+    use aide::OperationOutput;
+
+    fn default_response<T: OperationOutput>() -> serde_json::Value {
+        let responses = in_context(|ctx| T::inferred_responses(ctx, &mut Operation::default()));
+        assert_eq!(2, responses.len());
+
+        let (_, response) = responses
+            .into_iter()
+            .find(|(status, _)| status.is_none())
+            .unwrap();
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[test]
+    fn problem_is_documented_as_default_response() {
+        let response = default_response::<Result<String, HttpApiProblem>>();
+
+        assert_eq!(
+            response["content"][PROBLEM_JSON_MEDIA_TYPE]["schema"]["$ref"],
+            "#/components/schemas/HttpApiProblem"
+        );
+    }
+
+    #[cfg(feature = "api-error")]
+    #[test]
+    fn api_error_is_documented_as_default_response() {
+        let response = default_response::<Result<String, ApiError>>();
+
+        assert_eq!(
+            response["content"][PROBLEM_JSON_MEDIA_TYPE]["schema"]["$ref"],
+            "#/components/schemas/HttpApiProblem"
+        );
+    }
+}
+
+#[cfg(feature = "testing")]
+mod testing {
+    use crate::testing::ProblemAssert;
+    use crate::*;
+    use http::header::HeaderMap;
+    use serde_json::json;
+
+    fn out_of_credit() -> HttpApiProblem {
+        HttpApiProblem::with_title(StatusCode::FORBIDDEN)
+            .type_url("/probs/out-of-credit")
+            .detail("Your balance is 30.")
+            .value("balance", &30)
+            .value("accounts", &["/account/12345"])
+    }
+
+    fn response(problem: &HttpApiProblem) -> http::Response<String> {
+        Negotiator::default().to_http_response(problem, &HeaderMap::new())
+    }
+
+    #[tokio::test]
+    async fn checks_an_http_response() {
+        let problem = ProblemAssert::from_response(response(&out_of_credit()))
+            .await
+            .status(StatusCode::FORBIDDEN)
+            .type_url("/probs/out-of-credit")
+            .title("Forbidden")
+            .detail("Your balance is 30.")
+            .value("balance", 30)
+            .has_member("accounts")
+            .into_problem();
+
+        assert_eq!(out_of_credit(), problem);
+    }
+
+    #[tokio::test]
+    async fn macro_runs_the_checks() {
+        assert_problem!(
+            response(&out_of_credit()),
+            status(StatusCode::FORBIDDEN),
+            title("Forbidden"),
+            members(json!({ "balance": 30, "accounts": ["/account/12345"] })),
+        );
+    }
+
+    #[tokio::test]
+    async fn checks_invalid_params_and_problem_types() {
+        const INVALID_ORDER: ProblemType = ProblemType::new(
+            "invalid-order",
+            "/probs/invalid-order",
+            "The order is invalid.",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        );
+
+        let problem = ValidationProblem::new()
+            .query("page", "must be greater than 0")
+            .pointer("/items/0/name", "must not be empty")
+            .into_http_api_problem()
+            .type_url(INVALID_ORDER.type_url())
+            .title(INVALID_ORDER.title());
+
+        assert_problem!(
+            response(&problem),
+            problem_type(&INVALID_ORDER),
+            invalid_params(&["page", "items.0.name"]),
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "unexpected value of the extension member 'balance'")]
+    async fn panics_on_other_extension_members() {
+        assert_problem!(response(&out_of_credit()), value("balance", 50));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the content type 'application/problem+json'")]
+    fn panics_on_other_content_types() {
+        ProblemAssert::from_parts(
+            StatusCode::FORBIDDEN,
+            Some("application/json"),
+            out_of_credit().json_string().as_bytes(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the status of the response differs")]
+    fn panics_if_the_status_of_the_problem_differs() {
+        ProblemAssert::from_parts(
+            StatusCode::NOT_FOUND,
+            Some("application/problem+json; charset=utf-8"),
+            out_of_credit().json_string().as_bytes(),
+        );
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn checks_an_axum_response() {
+        assert_problem!(
+            out_of_credit().to_axum_response(),
+            status(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[test]
+    fn checks_an_actix_service_response() {
+        use actix_web::test::{call_service, init_service, TestRequest};
+        use actix_web::{web, App};
+
+        actix_web::rt::System::new().block_on(async {
+            let app = init_service(App::new().route(
+                "/",
+                web::get().to(|| async { out_of_credit().to_actix_response() }),
+            ))
+            .await;
+            let response = call_service(&app, TestRequest::get().to_request()).await;
+
+            assert_problem!(
+                response,
+                status(StatusCode::FORBIDDEN),
+                value("balance", 30)
+            );
+        });
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn checks_a_reqwest_response() {
+        let mut response = http::Response::new(out_of_credit().json_string());
+        *response.status_mut() = StatusCode::FORBIDDEN;
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            PROBLEM_JSON_MEDIA_TYPE.parse().unwrap(),
+        );
+
+        assert_problem!(
+            reqwest::Response::from(response),
+            type_url("/probs/out-of-credit")
+        );
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_arbitrary {
+    use crate::*;
+    use proptest::prelude::*;
+
+    const RESERVED_KEYS: &[&str] = &["type", "status", "title", "detail", "instance"];
+
+    fn is_extension_name(name: &str) -> bool {
+        name.len() >= 3
+            && name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    proptest! {
+        #[test]
+        fn json_round_trip(problem: HttpApiProblem) {
+            let parsed: HttpApiProblem = serde_json::from_str(&problem.json_string()).unwrap();
+            prop_assert_eq!(problem, parsed);
+        }
+
+        #[test]
+        fn problems_are_valid(problem: HttpApiProblem) {
+            if let Some(status) = problem.status {
+                prop_assert!(status.canonical_reason().is_some());
+                prop_assert!(status.is_client_error() || status.is_server_error());
+            }
+            if let Some(instance) = problem.instance.as_deref() {
+                prop_assert!(instance.starts_with('/'));
+            }
+            for key in problem.additional_fields().keys() {
+                prop_assert!(!RESERVED_KEYS.contains(&key.as_str()));
+                prop_assert!(is_extension_name(key), "invalid name {:?}", key);
+            }
+            prop_assert_eq!(Ok(()), problem.validate());
+        }
+    }
+
+    #[cfg(feature = "api-error")]
+    proptest! {
+        #[test]
+        fn api_error_round_trip(error: ApiError) {
+            let problem = error.to_http_api_problem();
+            let parsed: HttpApiProblem = serde_json::from_str(&problem.json_string()).unwrap();
+
+            prop_assert_eq!(Some(error.status()), parsed.status);
+            prop_assert_eq!(error.message(), parsed.detail.as_deref());
+            prop_assert_eq!(problem, parsed);
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_arbitrary {
+    use crate::*;
+    use quickcheck::{quickcheck, Arbitrary};
+
+    #[test]
+    fn json_round_trip() {
+        fn round_trip(problem: HttpApiProblem) -> bool {
+            serde_json::from_str::<HttpApiProblem>(&problem.json_string()).ok() == Some(problem)
+        }
+        quickcheck(round_trip as fn(HttpApiProblem) -> bool);
+    }
+
+    #[test]
+    fn problems_are_valid() {
+        fn valid(problem: HttpApiProblem) -> bool {
+            let status_is_error = problem
+                .status
+                .iter()
+                .all(|s| s.is_client_error() || s.is_server_error());
+            let names_are_valid = problem.additional_fields().keys().all(|key| {
+                key.len() >= 3
+                    && key.starts_with(|c: char| c.is_ascii_lowercase())
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+            status_is_error && names_are_valid && problem.validate().is_ok()
+        }
+        quickcheck(valid as fn(HttpApiProblem) -> bool);
+    }
+
+    #[test]
+    fn shrinking_removes_members() {
+        let problem = HttpApiProblem::with_title(StatusCode::NOT_FOUND).value("id", &42);
+
+        let shrunk: Vec<_> = problem.shrink().collect();
+
+        assert_eq!(3, shrunk.len());
+        assert!(shrunk.iter().any(|p| p.status.is_none()));
+        assert!(shrunk.iter().any(|p| p.title.is_none()));
+        assert!(shrunk.iter().any(|p| p.additional_fields().is_empty()));
+    }
+}
@@ -0,0 +1,391 @@
+//! Assertions for problem responses in tests
+//!
+//! [ProblemAssert] reads a response and checks that it is a problem:
+//! the `Content-Type` has to be `application/problem+json` and the body
+//! has to be a problem JSON. Further checks are chained and panic with
+//! the whole problem in the message if they fail.
+//!
+//! [ProblemAssert::from_response] accepts every [http::Response] with a
+//! body implementing [http_body::Body] (hyper, axum, tower, ...), the
+//! responses of actix-web (feature `actix-web`) and reqwest (feature `reqwest`,
+//! which implies `testing`).
+//!
+//! The [assert_problem!] macro reads the response and runs the given checks.
+//! It has to be used within an `async` context.
+//!
+//! Requires the `testing` feature
+//!
+//! # Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//! use http_api_problem::testing::ProblemAssert;
+//!
+//! # tokio_test();
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn tokio_test() {
+//! let problem = HttpApiProblem::with_title(StatusCode::FORBIDDEN)
+//!     .type_url("/probs/out-of-credit")
+//!     .value("balance", &30);
+//!
+//! let negotiator = Negotiator::default();
+//! let response = negotiator.to_http_response(&problem, &http::HeaderMap::new());
+//!
+//! ProblemAssert::from_response(response)
+//!     .await
+//!     .status(StatusCode::FORBIDDEN)
+//!     .type_url("/probs/out-of-credit")
+//!     .value("balance", 30);
+//!
+//! let response = negotiator.to_http_response(&problem, &http::HeaderMap::new());
+//! assert_problem!(response, status(StatusCode::FORBIDDEN), title("Forbidden"));
+//! # }
+//! ```
+use std::future::Future;
+
+use http_body_util::BodyExt;
+use serde_json::Value;
+
+use super::*;
+
+pub use crate::assert_problem;
+
+/// Reads the response and checks that it is a problem, then runs the given checks.
+///
+/// Every check is a method of [ProblemAssert](testing::ProblemAssert), written
+/// like a call of the method. The macro evaluates to the
+/// [ProblemAssert](testing::ProblemAssert) for further checks.
+///
+/// Has to be used within an `async` context.
+///
+/// Requires the `testing` feature
+///
+/// #Example
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use http_api_problem::*;
+///
+/// let problem = HttpApiProblem::new(StatusCode::FORBIDDEN)
+///     .type_url("/probs/out-of-credit")
+///     .title("You do not have enough credit.")
+///     .value("balance", &30);
+/// let response = Negotiator::default().to_http_response(&problem, &http::HeaderMap::new());
+///
+/// assert_problem!(
+///     response,
+///     status(StatusCode::FORBIDDEN),
+///     type_url("/probs/out-of-credit"),
+///     title("You do not have enough credit."),
+///     value("balance", 30),
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_problem {
+    ($response:expr $(, $check:ident($($arg:expr),* $(,)?))* $(,)?) => {{
+        let assert = $crate::testing::ProblemAssert::from_response($response).await;
+        $(
+            let assert = assert.$check($($arg),*);
+        )*
+        assert
+    }};
+}
+
+/// A response which can be checked with a [ProblemAssert]
+///
+/// Requires the `testing` feature
+pub trait ProblemResponse {
+    /// Reads the status, the `Content-Type` header and the body of the response.
+    fn into_parts(self) -> impl Future<Output = (StatusCode, Option<String>, Vec<u8>)>;
+}
+
+impl<B> ProblemResponse for http::Response<B>
+where
+    B: http_body::Body,
+    B::Error: fmt::Debug,
+{
+    async fn into_parts(self) -> (StatusCode, Option<String>, Vec<u8>) {
+        let (parts, body) = self.into_parts();
+        let content_type = parts
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = body
+            .collect()
+            .await
+            .expect("failed to read the body of the response")
+            .to_bytes();
+
+        (parts.status, content_type, body.to_vec())
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl<B> ProblemResponse for actix_web::HttpResponse<B>
+where
+    B: actix_web::body::MessageBody,
+{
+    async fn into_parts(self) -> (StatusCode, Option<String>, Vec<u8>) {
+        let status = actix_status(self.status());
+        let content_type = actix_content_type(self.headers());
+        let body = actix_web::body::to_bytes(self.into_body())
+            .await
+            .ok()
+            .expect("failed to read the body of the response");
+
+        (status, content_type, body.to_vec())
+    }
+}
+
+/// The response of actix-web's `test::call_service`
+#[cfg(feature = "actix-web")]
+impl<B> ProblemResponse for actix_web::dev::ServiceResponse<B>
+where
+    B: actix_web::body::MessageBody,
+{
+    async fn into_parts(self) -> (StatusCode, Option<String>, Vec<u8>) {
+        ProblemResponse::into_parts(self.into_parts().1).await
+    }
+}
+
+#[cfg(feature = "actix-web")]
+fn actix_status(status: actix_web::http::StatusCode) -> StatusCode {
+    StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[cfg(feature = "actix-web")]
+fn actix_content_type(headers: &actix_web::http::header::HeaderMap) -> Option<String> {
+    headers
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(feature = "reqwest")]
+impl ProblemResponse for reqwest::Response {
+    async fn into_parts(self) -> (StatusCode, Option<String>, Vec<u8>) {
+        let status = self.status();
+        let content_type = self
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = self
+            .bytes()
+            .await
+            .expect("failed to read the body of the response");
+
+        (status, content_type, body.to_vec())
+    }
+}
+
+/// Checks a problem response
+///
+/// Every check panics if it fails.
+///
+/// Requires the `testing` feature
+#[derive(Debug, Clone)]
+pub struct ProblemAssert {
+    status: StatusCode,
+    problem: HttpApiProblem,
+    json: Value,
+}
+
+impl ProblemAssert {
+    /// Reads the response and checks that it is a problem.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Content-Type` is not `application/problem+json`,
+    /// the body is not a problem JSON or the `status` of the problem
+    /// differs from the status of the response.
+    pub async fn from_response<R: ProblemResponse>(response: R) -> Self {
+        let (status, content_type, body) = response.into_parts().await;
+        Self::from_parts(status, content_type.as_deref(), &body)
+    }
+
+    /// Checks the parts of a response.
+    ///
+    /// Use this for clients which are not supported by [ProblemAssert::from_response].
+    ///
+    /// # Panics
+    ///
+    /// Panics like [ProblemAssert::from_response]
+    #[track_caller]
+    pub fn from_parts(status: StatusCode, content_type: Option<&str>, body: &[u8]) -> Self {
+        let media_type = content_type
+            .and_then(|v| v.split(';').next())
+            .map(str::trim);
+        assert!(
+            media_type.is_some_and(|v| v.eq_ignore_ascii_case(PROBLEM_JSON_MEDIA_TYPE)),
+            "expected the content type '{}' but was {:?}. body: {}",
+            PROBLEM_JSON_MEDIA_TYPE,
+            content_type,
+            String::from_utf8_lossy(body)
+        );
+
+        let json: Value = serde_json::from_slice(body).unwrap_or_else(|err| {
+            panic!(
+                "the body is not JSON: {}. body: {}",
+                err,
+                String::from_utf8_lossy(body)
+            )
+        });
+        let problem: HttpApiProblem = serde_json::from_value(json.clone())
+            .unwrap_or_else(|err| panic!("the body is not a problem: {}. body: {}", err, json));
+
+        if let Some(problem_status) = problem.status {
+            assert_eq!(
+                status, problem_status,
+                "the status of the response differs from the status of the problem. problem: {}",
+                json
+            );
+        }
+
+        Self {
+            status,
+            problem,
+            json,
+        }
+    }
+
+    /// The problem of the response
+    pub fn problem(&self) -> &HttpApiProblem {
+        &self.problem
+    }
+
+    /// Returns the problem of the response
+    pub fn into_problem(self) -> HttpApiProblem {
+        self.problem
+    }
+
+    /// Checks the status of the response
+    #[track_caller]
+    pub fn status<T: Into<StatusCode>>(self, status: T) -> Self {
+        assert_eq!(
+            status.into(),
+            self.status,
+            "unexpected status. problem: {}",
+            self.json
+        );
+        self
+    }
+
+    /// Checks the `type` of the problem
+    #[track_caller]
+    pub fn type_url(self, type_url: &str) -> Self {
+        assert_eq!(
+            Some(type_url),
+            self.problem.type_url.as_deref(),
+            "unexpected type. problem: {}",
+            self.json
+        );
+        self
+    }
+
+    /// Checks the `title` of the problem
+    #[track_caller]
+    pub fn title(self, title: &str) -> Self {
+        assert_eq!(
+            Some(title),
+            self.problem.title.as_deref(),
+            "unexpected title. problem: {}",
+            self.json
+        );
+        self
+    }
+
+    /// Checks the `detail` of the problem
+    #[track_caller]
+    pub fn detail(self, detail: &str) -> Self {
+        assert_eq!(
+            Some(detail),
+            self.problem.detail.as_deref(),
+            "unexpected detail. problem: {}",
+            self.json
+        );
+        self
+    }
+
+    /// Checks the `instance` of the problem
+    #[track_caller]
+    pub fn instance(self, instance: &str) -> Self {
+        assert_eq!(
+            Some(instance),
+            self.problem.instance.as_deref(),
+            "unexpected instance. problem: {}",
+            self.json
+        );
+        self
+    }
+
+    /// Checks that the problem is of the given [ProblemType]
+    ///
+    /// The `type` and the `title` have to be the ones of the problem type.
+    #[track_caller]
+    pub fn problem_type(self, problem_type: &ProblemType) -> Self {
+        self.type_url(problem_type.type_url())
+            .title(problem_type.title())
+    }
+
+    /// Checks the value of an extension member
+    #[track_caller]
+    pub fn value<V: Serialize>(self, key: &str, value: V) -> Self {
+        let expected = serde_json::to_value(value).expect("the value is not serializable");
+        assert_eq!(
+            Some(&expected),
+            self.problem.json_value(key),
+            "unexpected value of the extension member '{}'. problem: {}",
+            key,
+            self.json
+        );
+        self
+    }
+
+    /// Checks the values of the extension members in the JSON object.
+    ///
+    /// Other extension members of the problem are ignored.
+    #[track_caller]
+    pub fn members(self, members: Value) -> Self {
+        let members = match members {
+            Value::Object(members) => members,
+            other => panic!(
+                "expected a JSON object of extension members but got {}",
+                other
+            ),
+        };
+
+        members
+            .into_iter()
+            .fold(self, |assert, (key, value)| assert.value(&key, value))
+    }
+
+    /// Checks that the problem has the extension member
+    #[track_caller]
+    pub fn has_member(self, key: &str) -> Self {
+        assert!(
+            self.problem.json_value(key).is_some(),
+            "the extension member '{}' is missing. problem: {}",
+            key,
+            self.json
+        );
+        self
+    }
+
+    /// Checks the `invalid-params` of the problem by their names
+    #[track_caller]
+    pub fn invalid_params(self, names: &[&str]) -> Self {
+        let params = self.problem.invalid_params().unwrap_or_default();
+        let actual: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            actual.as_slice(),
+            "unexpected invalid-params. problem: {}",
+            self.json
+        );
+        self
+    }
+}
@@ -0,0 +1,244 @@
+//! Conversions between problems and [tonic::Status]
+//!
+//! The HTTP status of a problem is mapped to a gRPC [Code] as described by
+//! [google.rpc.Code](https://github.com/googleapis/googleapis/blob/master/google/rpc/code.proto)
+//! and back ([grpc_code_for_status] and [status_for_grpc_code]).
+//!
+//! The message of the [tonic::Status] is the `detail` (or the `title`) of the
+//! problem. The whole problem travels in the binary details
+//! (`grpc-status-details-bin`) as a `google.rpc.Status` containing
+//!
+//! * an `ErrorInfo` with the domain [PROBLEM_ERROR_INFO_DOMAIN], the status
+//!   as its reason (e.g. `NOT_FOUND`) and the problem JSON as the metadata `problem`
+//! * a `BadRequest` with a violation for every [InvalidParam] of the problem
+//!
+//! A [tonic::Status] carrying such details converts back into the very same
+//! problem. For any other [tonic::Status] a problem is created from its code,
+//! message and `BadRequest` details.
+//!
+//! #Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//!
+//! let problem = HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND)
+//!     .detail("no user with id 42");
+//!
+//! let status = tonic::Status::from(problem.clone());
+//! assert_eq!(tonic::Code::NotFound, status.code());
+//! assert_eq!("no user with id 42", status.message());
+//!
+//! assert_eq!(problem, HttpApiProblem::from(status));
+//! ```
+use std::collections::HashMap;
+
+use tonic::Code;
+use tonic_types::{ErrorDetails, StatusExt};
+
+use super::*;
+
+/// The domain of the `ErrorInfo` detail which contains a problem
+pub const PROBLEM_ERROR_INFO_DOMAIN: &str = "http-api-problem";
+
+const PROBLEM_METADATA_KEY: &str = "problem";
+
+/// Maps an HTTP status to a gRPC [Code]
+///
+/// The mapping follows the HTTP statuses given in the comments of
+/// [`google.rpc.Code`](https://github.com/googleapis/googleapis/blob/master/google/rpc/code.proto)
+/// like [status_for_grpc_code]. This intentionally differs from
+/// gRPC's [HTTP to gRPC status mapping](https://github.com/grpc/grpc/blob/master/doc/http-grpc-status-mapping.md)
+/// for proxies, which maps e.g. `400` to `INTERNAL` and `404` to `UNIMPLEMENTED`.
+///
+/// Requires the `tonic` feature
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// assert_eq!(tonic::Code::InvalidArgument, grpc_code_for_status(StatusCode::BAD_REQUEST));
+/// assert_eq!(tonic::Code::Unavailable, grpc_code_for_status(StatusCode::SERVICE_UNAVAILABLE));
+/// assert_eq!(tonic::Code::FailedPrecondition, grpc_code_for_status(StatusCode::IM_A_TEAPOT));
+/// ```
+pub fn grpc_code_for_status(status: StatusCode) -> Code {
+    match status.as_u16() {
+        200..=299 => Code::Ok,
+        400 | 422 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 | 410 => Code::NotFound,
+        405 | 501 => Code::Unimplemented,
+        408 | 504 => Code::DeadlineExceeded,
+        409 => Code::Aborted,
+        412 => Code::FailedPrecondition,
+        413 | 429 => Code::ResourceExhausted,
+        416 => Code::OutOfRange,
+        499 => Code::Cancelled,
+        502 | 503 => Code::Unavailable,
+        400..=499 => Code::FailedPrecondition,
+        500..=599 => Code::Internal,
+        _ => Code::Unknown,
+    }
+}
+
+/// Maps a gRPC [Code] to an HTTP status
+///
+/// Requires the `tonic` feature
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// assert_eq!(StatusCode::CONFLICT, status_for_grpc_code(tonic::Code::AlreadyExists));
+/// assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, status_for_grpc_code(tonic::Code::DataLoss));
+/// ```
+pub fn status_for_grpc_code(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).expect("499 is a valid status code"),
+        Code::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        Code::InvalidArgument => StatusCode::BAD_REQUEST,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::FailedPrecondition => StatusCode::BAD_REQUEST,
+        Code::Aborted => StatusCode::CONFLICT,
+        Code::OutOfRange => StatusCode::BAD_REQUEST,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+    }
+}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Creates a [tonic::Status] carrying this problem in its details.
+    ///
+    /// If status is `None` `500 - Internal Server Error` is the
+    /// default. A problem which can not be serialized becomes a
+    /// `500 - Internal Server Error` problem.
+    ///
+    /// Requires the `tonic` feature
+    pub fn to_tonic_status(&self) -> tonic::Status {
+        let mut members = match self.extension_members_json() {
+            Ok(members) => members,
+            Err(_) => return unserializable_problem().to_tonic_status(),
+        };
+        let json = match serde_json::to_string(self) {
+            Ok(json) => json,
+            Err(_) => return unserializable_problem().to_tonic_status(),
+        };
+
+        let status = self.status_or_internal_server_error();
+        let message = self
+            .detail
+            .as_deref()
+            .or(self.title.as_deref())
+            .unwrap_or_default();
+
+        let mut details = ErrorDetails::new();
+        details.set_error_info(
+            error_info_reason(status),
+            PROBLEM_ERROR_INFO_DOMAIN,
+            HashMap::from([(PROBLEM_METADATA_KEY.to_string(), json)]),
+        );
+
+        let invalid_params = members
+            .remove(INVALID_PARAMS_KEY)
+            .and_then(|value| serde_json::from_value::<Vec<InvalidParam>>(value).ok())
+            .unwrap_or_default();
+        for param in invalid_params {
+            details.add_bad_request_violation(param.name, param.reason);
+        }
+
+        tonic::Status::with_error_details(grpc_code_for_status(status), message, details)
+    }
+}
+
+/// `NOT_FOUND` for `404 - Not Found`
+fn error_info_reason(status: StatusCode) -> String {
+    status
+        .canonical_reason()
+        .unwrap_or("UNKNOWN")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl<Ext: Serialize> From<HttpApiProblem<Ext>> for tonic::Status {
+    fn from(problem: HttpApiProblem<Ext>) -> Self {
+        problem.to_tonic_status()
+    }
+}
+
+impl From<&tonic::Status> for HttpApiProblem {
+    fn from(status: &tonic::Status) -> Self {
+        let details = status.get_error_details();
+
+        let problem = details
+            .error_info()
+            .filter(|info| info.domain == PROBLEM_ERROR_INFO_DOMAIN)
+            .and_then(|info| info.metadata.get(PROBLEM_METADATA_KEY))
+            .and_then(|json| serde_json::from_str::<HttpApiProblem>(json).ok());
+        if let Some(problem) = problem {
+            return problem;
+        }
+
+        let mut problem = HttpApiProblem::with_title_and_type(status_for_grpc_code(status.code()));
+        if !status.message().is_empty() {
+            problem.detail = Some(status.message().to_string());
+        }
+
+        if let Some(bad_request) = details.bad_request() {
+            let invalid_params: Vec<InvalidParam> = bad_request
+                .field_violations
+                .iter()
+                .map(|violation| InvalidParam::new(&violation.field, &violation.description))
+                .collect();
+            problem.set_value(INVALID_PARAMS_KEY, &invalid_params);
+        }
+
+        problem
+    }
+}
+
+impl From<tonic::Status> for HttpApiProblem {
+    fn from(status: tonic::Status) -> Self {
+        HttpApiProblem::from(&status)
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl From<ApiError> for tonic::Status {
+    fn from(error: ApiError) -> Self {
+        error.into_http_api_problem().to_tonic_status()
+    }
+}
+
+/// The [tonic::Status] is kept as the source of the [ApiError].
+#[cfg(feature = "api-error")]
+impl IntoApiError for tonic::Status {
+    fn into_api_error(self) -> ApiError {
+        let problem = HttpApiProblem::from(&self);
+
+        let mut builder = ApiError::builder(problem.status_or_internal_server_error());
+        builder.title = problem.title;
+        builder.message = problem.detail;
+        builder.type_url = problem.type_url;
+        builder.instance = problem.instance;
+        builder.fields = problem.additional_fields;
+
+        builder.source(self).finish()
+    }
+}
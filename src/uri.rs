@@ -0,0 +1,267 @@
+//! Minimal handling of URI references as defined by
+//! [RFC3986](https://tools.ietf.org/html/rfc3986).
+//!
+//! Only what is needed to deal with the `type` and `instance`
+//! members of a problem is implemented here.
+
+/// The components of a URI reference
+/// ([RFC3986, Section 3](https://tools.ietf.org/html/rfc3986#section-3))
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Components<'a> {
+    fn parse(reference: &'a str) -> Self {
+        let (rest, fragment) = match reference.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (reference, None),
+        };
+
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+
+        let (scheme, rest) = match split_scheme(rest) {
+            Some((scheme, rest)) => (Some(scheme), rest),
+            None => (None, rest),
+        };
+
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let idx = rest.find('/').unwrap_or(rest.len());
+                (Some(&rest[..idx]), &rest[idx..])
+            }
+            None => (None, rest),
+        };
+
+        Components {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+fn split_scheme(s: &str) -> Option<(&str, &str)> {
+    let idx = s.find(':')?;
+    let scheme = &s[..idx];
+    if is_scheme(scheme) {
+        Some((scheme, &s[idx + 1..]))
+    } else {
+        None
+    }
+}
+
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+}
+
+/// Returns `true` if the reference has a scheme and therefore is not a
+/// relative reference.
+pub(crate) fn is_absolute(reference: &str) -> bool {
+    Components::parse(reference).scheme.is_some()
+}
+
+/// Returns `true` if the reference is safe to be linked from a page,
+/// i.e. it is a relative reference or has the scheme `http` or `https`.
+pub(crate) fn is_linkable(reference: &str) -> bool {
+    if !is_uri_reference(reference) {
+        return false;
+    }
+
+    match Components::parse(reference).scheme {
+        Some(scheme) => scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"),
+        None => true,
+    }
+}
+
+/// Returns the path of a relative reference or of an absolute reference
+/// with an authority. Absolute references without an authority, e.g.
+/// `about:blank`, have no path which could be served.
+pub(crate) fn served_path(reference: &str) -> Option<&str> {
+    let r = Components::parse(reference);
+    match (r.scheme, r.authority) {
+        (Some(_), None) => None,
+        _ => Some(r.path),
+    }
+}
+
+/// Returns `true` if the string is a URI reference as defined in
+/// [RFC3986, Section 4.1](https://tools.ietf.org/html/rfc3986#section-4.1).
+///
+/// The characters, percent-encodings and the placement of the delimiters
+/// are checked. The authority is not checked beyond its characters.
+pub(crate) fn is_uri_reference(reference: &str) -> bool {
+    if !has_uri_characters(reference) {
+        return false;
+    }
+
+    let r = Components::parse(reference);
+
+    if r.fragment.is_some_and(|fragment| fragment.contains('#')) {
+        return false;
+    }
+
+    // IP literals are the only place for brackets
+    let outside_authority = [Some(r.path), r.query, r.fragment];
+    if outside_authority
+        .iter()
+        .flatten()
+        .any(|part| part.contains(['[', ']']))
+    {
+        return false;
+    }
+
+    // A colon in the first segment of a relative path would make it a scheme
+    if r.scheme.is_none() && r.authority.is_none() {
+        let first_segment = r.path.split('/').next().unwrap_or_default();
+        if first_segment.contains(':') {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Only unreserved characters, reserved characters and
+/// percent-encodings are allowed
+fn has_uri_characters(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let b = bytes[idx];
+        if b == b'%' {
+            let encoded = bytes.get(idx + 1..idx + 3);
+            if !encoded.is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                return false;
+            }
+            idx += 3;
+        } else if b.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=".contains(&b) {
+            idx += 1;
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+/// Resolves `reference` against `base` as described in
+/// [RFC3986, Section 5.2](https://tools.ietf.org/html/rfc3986#section-5.2).
+///
+/// `base` should be an absolute URI. If it is not, the result is
+/// still computed but might itself be a relative reference.
+pub(crate) fn resolve(base: &str, reference: &str) -> String {
+    let r = Components::parse(reference);
+    let b = Components::parse(base);
+
+    let scheme;
+    let authority;
+    let path;
+    let query;
+
+    if r.scheme.is_some() {
+        scheme = r.scheme;
+        authority = r.authority;
+        path = remove_dot_segments(r.path);
+        query = r.query;
+    } else {
+        if r.authority.is_some() {
+            authority = r.authority;
+            path = remove_dot_segments(r.path);
+            query = r.query;
+        } else {
+            if r.path.is_empty() {
+                path = b.path.to_string();
+                query = r.query.or(b.query);
+            } else {
+                if r.path.starts_with('/') {
+                    path = remove_dot_segments(r.path);
+                } else {
+                    path = remove_dot_segments(&merge(&b, r.path));
+                }
+                query = r.query;
+            }
+            authority = b.authority;
+        }
+        scheme = b.scheme;
+    }
+
+    let mut target = String::new();
+    if let Some(scheme) = scheme {
+        target.push_str(scheme);
+        target.push(':');
+    }
+    if let Some(authority) = authority {
+        target.push_str("//");
+        target.push_str(authority);
+    }
+    target.push_str(&path);
+    if let Some(query) = query {
+        target.push('?');
+        target.push_str(query);
+    }
+    if let Some(fragment) = r.fragment {
+        target.push('#');
+        target.push_str(fragment);
+    }
+
+    target
+}
+
+fn merge(base: &Components, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        return format!("/{}", path);
+    }
+
+    match base.path.rfind('/') {
+        Some(idx) => format!("{}{}", &base.path[..=idx], path),
+        None => path.to_string(),
+    }
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output: Vec<&str> = Vec::new();
+
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            output.pop();
+        } else if input == "/.." {
+            input = "/";
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..]
+                .find('/')
+                .map(|idx| idx + start)
+                .unwrap_or(input.len());
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+
+    output.concat()
+}
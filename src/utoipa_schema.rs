@@ -0,0 +1,242 @@
+//! [utoipa](https://docs.rs/utoipa) support for problems
+//!
+//! [HttpApiProblem] implements [ToSchema] and, like `ApiError` (with the
+//! feature `api-error`), [IntoResponses] which documents a `default` response
+//! with an `application/problem+json` body.
+//!
+//! The schema of an untyped [HttpApiProblem] is named `HttpApiProblem`. The
+//! properties of typed extension members are added to the schema which is
+//! then named e.g. `HttpApiProblem_for_InvalidParams`.
+//!
+//! [ProblemResponses] documents the problem responses of an operation
+//! with examples built from the errors a handler actually returns.
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use utoipa::openapi::example::ExampleBuilder;
+use utoipa::openapi::response::{Response, ResponseBuilder, Responses};
+use utoipa::openapi::schema::AdditionalProperties;
+use utoipa::openapi::{ContentBuilder, ObjectBuilder, Ref, RefOr, Schema, Type};
+use utoipa::{IntoResponses, PartialSchema, ToSchema};
+
+use super::*;
+
+use super::schema_descriptions::SCHEMA_NAME;
+
+impl<Ext: ToSchema> PartialSchema for HttpApiProblem<Ext> {
+    fn schema() -> RefOr<Schema> {
+        let property = |schema_type: Type, description: &str| {
+            ObjectBuilder::new()
+                .schema_type(schema_type)
+                .description(Some(description))
+        };
+
+        let mut schema = ObjectBuilder::new()
+            .schema_type(Type::Object)
+            .description(Some(schema_descriptions::PROBLEM))
+            .property("type", property(Type::String, schema_descriptions::TYPE))
+            .property(
+                "status",
+                property(Type::Integer, schema_descriptions::STATUS),
+            )
+            .property("title", property(Type::String, schema_descriptions::TITLE))
+            .property(
+                "detail",
+                property(Type::String, schema_descriptions::DETAIL),
+            )
+            .property(
+                "instance",
+                property(Type::String, schema_descriptions::INSTANCE),
+            )
+            .additional_properties(Some(AdditionalProperties::<Schema>::FreeForm(true)))
+            .build();
+
+        if let RefOr::T(Schema::Object(ext)) = Ext::schema() {
+            schema.properties.extend(ext.properties);
+            schema.required.extend(ext.required);
+        }
+
+        Schema::Object(schema).into()
+    }
+}
+
+impl<Ext: ToSchema> ToSchema for HttpApiProblem<Ext> {
+    fn name() -> Cow<'static, str> {
+        if is_unit::<Ext>() {
+            Cow::Borrowed(SCHEMA_NAME)
+        } else {
+            Cow::Owned(format!("{}_for_{}", SCHEMA_NAME, Ext::name()))
+        }
+    }
+
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        Ext::schemas(schemas);
+    }
+}
+
+fn is_unit<T: ToSchema>() -> bool {
+    T::name() == <()>::name()
+}
+
+impl IntoResponses for HttpApiProblem {
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        default_problem_response()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoResponses for ApiError {
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        default_problem_response()
+    }
+}
+
+fn default_problem_response() -> BTreeMap<String, RefOr<Response>> {
+    let response = ResponseBuilder::new()
+        .description(schema_descriptions::RESPONSE)
+        .content(
+            PROBLEM_JSON_MEDIA_TYPE,
+            ContentBuilder::new()
+                .schema(Some(Ref::from_schema_name(SCHEMA_NAME)))
+                .build(),
+        )
+        .build();
+
+    BTreeMap::from([("default".to_string(), response.into())])
+}
+
+/// Documents problem responses with examples
+///
+/// Every problem (or `ApiError`) added becomes an example of the response
+/// for its status. The responses refer to the `HttpApiProblem` schema which
+/// has to be added to the components.
+///
+/// Requires the `utoipa` feature
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// let responses = ProblemResponses::new()
+///     .problem(&HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND).detail("no such user"))
+///     .problem(&HttpApiProblem::new(StatusCode::CONFLICT).title("Name taken"))
+///     .problem(&HttpApiProblem::new(StatusCode::CONFLICT).title("Email taken"))
+///     .into_responses();
+///
+/// assert_eq!(vec!["404", "409"], responses.keys().collect::<Vec<_>>());
+/// ```
+///
+/// To add the responses to an operation of an `OpenApi` use
+/// `utoipa::Modify`:
+///
+/// ```rust
+/// use http_api_problem::*;
+/// use utoipa::openapi::OpenApi;
+/// use utoipa::Modify;
+/// # use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathItem};
+/// # use utoipa::openapi::{OpenApiBuilder, PathsBuilder};
+///
+/// struct UserProblems;
+///
+/// impl Modify for UserProblems {
+///     fn modify(&self, openapi: &mut OpenApi) {
+///         let operation = openapi.paths.paths.get_mut("/users/{id}").unwrap().get.as_mut().unwrap();
+///         let responses = ProblemResponses::new()
+///             .problem(&HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND))
+///             .into_responses();
+///         operation.responses.responses.extend(responses);
+///     }
+/// }
+/// # let operation = OperationBuilder::new().build();
+/// # let paths = PathsBuilder::new().path("/users/{id}", PathItem::new(HttpMethod::Get, operation));
+/// # let mut openapi = OpenApiBuilder::new().paths(paths).build();
+///
+/// UserProblems.modify(&mut openapi);
+/// # assert!(openapi.paths.paths["/users/{id}"].get.as_ref().unwrap().responses.responses.contains_key("404"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProblemResponses {
+    examples: BTreeMap<u16, Vec<(String, serde_json::Value)>>,
+}
+
+impl ProblemResponses {
+    /// Creates an empty instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the problem as an example of the response for its status
+    ///
+    /// If status is `None` `500 - Internal Server Error` is assumed.
+    pub fn problem<Ext: Serialize>(mut self, problem: &HttpApiProblem<Ext>) -> Self {
+        let status = problem.status_or_internal_server_error();
+        let examples = self.examples.entry(status.as_u16()).or_default();
+
+        let name = problem
+            .title
+            .clone()
+            .or_else(|| status.canonical_reason().map(str::to_string))
+            .unwrap_or_else(|| status.as_u16().to_string());
+        let name = if examples.iter().any(|(n, _)| *n == name) {
+            format!("{} ({})", name, examples.len() + 1)
+        } else {
+            name
+        };
+
+        let value = serde_json::to_value(problem).unwrap_or_default();
+        examples.push((name, value));
+        self
+    }
+
+    /// Adds the problem of the [ApiError] as an example of the
+    /// response for its status
+    ///
+    /// Requires the feature `api-error`
+    #[cfg(feature = "api-error")]
+    pub fn error(self, error: &ApiError) -> Self {
+        self.problem(&error.to_http_api_problem())
+    }
+
+    /// Creates the responses keyed by status code
+    pub fn into_responses(self) -> BTreeMap<String, RefOr<Response>> {
+        self.examples
+            .into_iter()
+            .map(|(status, examples)| {
+                let description = StatusCode::from_u16(status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .unwrap_or_default();
+
+                let examples = examples.into_iter().map(|(name, value)| {
+                    let example = ExampleBuilder::new()
+                        .summary(name.clone())
+                        .value(Some(value))
+                        .build();
+                    (name, example)
+                });
+
+                let response = ResponseBuilder::new()
+                    .description(description)
+                    .content(
+                        PROBLEM_JSON_MEDIA_TYPE,
+                        ContentBuilder::new()
+                            .schema(Some(Ref::from_schema_name(SCHEMA_NAME)))
+                            .examples_from_iter(examples)
+                            .build(),
+                    )
+                    .build();
+
+                (status.to_string(), response.into())
+            })
+            .collect()
+    }
+}
+
+impl From<ProblemResponses> for Responses {
+    fn from(responses: ProblemResponses) -> Self {
+        let mut result = Responses::new();
+        result.responses = responses.into_responses();
+        result
+    }
+}
@@ -0,0 +1,430 @@
+//! Problems describing invalid request parameters.
+//!
+//! The example of [RFC7807](https://tools.ietf.org/html/rfc7807#section-3)
+//! describes invalid request parameters with the extension member `invalid-params`:
+//!
+//! ```javascript
+//! {
+//!    "type": "https://example.net/validation-error",
+//!    "title": "Your request parameters didn't validate.",
+//!    "invalid-params": [
+//!        {
+//!            "name": "age",
+//!            "pointer": "/age",
+//!            "reason": "must be a positive integer"
+//!        },
+//!        {
+//!            "name": "color",
+//!            "reason": "must be 'green', 'red' or 'blue'",
+//!            "code": "one_of"
+//!        }
+//!    ]
+//! }
+//! ```
+//!
+//! A [ValidationProblem] collects [InvalidParam]s and renders them
+//! as this extension member. Clients can read them with
+//! [HttpApiProblem::invalid_params].
+//!
+//! With the features `validator` and `garde` the validation errors of
+//! these crates can be converted into a [ValidationProblem], an
+//! [HttpApiProblem] and an `ApiError` (with the feature `api-error`).
+//! Nested errors are flattened into one [InvalidParam] per error.
+use super::*;
+
+#[cfg(feature = "garde")]
+mod garde;
+#[cfg(feature = "validator")]
+mod validator;
+
+/// The name of the extension member containing the [InvalidParam]s
+///
+/// "invalid-params"
+pub static INVALID_PARAMS_KEY: &str = "invalid-params";
+
+/// A single invalid parameter of a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct InvalidParam {
+    /// The name of the parameter.
+    ///
+    /// For members of a request body this is the path of the
+    /// member with the segments separated by a `.`.
+    pub name: String,
+
+    /// A [JSON Pointer](https://tools.ietf.org/html/rfc6901) to the
+    /// invalid member of a request body or of the path parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+
+    /// A human-readable explanation why the parameter is invalid
+    pub reason: String,
+
+    /// A machine readable code for the violation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl InvalidParam {
+    /// Creates a new instance for a parameter which is not part
+    /// of the request body, e.g. a query parameter.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = InvalidParam::new("page", "must be greater than 0");
+    ///
+    /// assert_eq!("page", p.name);
+    /// assert_eq!(None, p.pointer);
+    /// ```
+    pub fn new<N: Into<String>, R: Into<String>>(name: N, reason: R) -> Self {
+        InvalidParam {
+            name: name.into(),
+            pointer: None,
+            reason: reason.into(),
+            code: None,
+        }
+    }
+
+    /// Creates a new instance for a member of the request body
+    /// identified by a [JSON Pointer](https://tools.ietf.org/html/rfc6901).
+    ///
+    /// The `name` is derived from the pointer.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = InvalidParam::at_pointer("/items/0/a~1b", "must not be empty");
+    ///
+    /// assert_eq!("items.0.a/b", p.name);
+    /// assert_eq!(Some("/items/0/a~1b"), p.pointer.as_deref());
+    /// ```
+    pub fn at_pointer<P: Into<String>, R: Into<String>>(pointer: P, reason: R) -> Self {
+        let pointer = pointer.into();
+        InvalidParam {
+            name: name_from_pointer(&pointer),
+            pointer: Some(pointer),
+            reason: reason.into(),
+            code: None,
+        }
+    }
+
+    /// Creates a new instance from the message of a serde error
+    /// which names a field, e.g. "missing field `name`".
+    ///
+    /// Returns `None` if the message does not name a field.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = InvalidParam::from_serde_message("missing field `page`").unwrap();
+    ///
+    /// assert_eq!("page", p.name);
+    /// assert_eq!("missing field `page`", p.reason);
+    ///
+    /// assert!(InvalidParam::from_serde_message("invalid digit found in string").is_none());
+    /// ```
+    pub fn from_serde_message(message: &str) -> Option<Self> {
+        ["missing field `", "unknown field `", "duplicate field `"]
+            .iter()
+            .find_map(|prefix| message.strip_prefix(prefix))
+            .and_then(|rest| rest.split('`').next())
+            .map(|name| InvalidParam::new(name, message))
+    }
+
+    /// Sets a machine readable code for the violation
+    pub fn code<C: Into<String>>(mut self, code: C) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+/// The `invalid-params` extension member as typed extension members.
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// let json = r#"{
+///     "status": 400,
+///     "invalid-params": [{"name": "age", "pointer": "/age", "reason": "must be a positive integer"}]
+/// }"#;
+///
+/// let p: HttpApiProblem<InvalidParams> = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!("must be a positive integer", p.extension_members.invalid_params[0].reason);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct InvalidParams {
+    /// The invalid parameters
+    #[serde(rename = "invalid-params", default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_params: Vec<InvalidParam>,
+}
+
+/// Creates a [JSON Pointer](https://tools.ietf.org/html/rfc6901) from its
+/// unescaped reference tokens.
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// assert_eq!("/items/0/a~1b~0c", json_pointer(["items", "0", "a/b~c"]));
+/// assert_eq!("", json_pointer(Vec::<String>::new()));
+/// ```
+pub fn json_pointer<I, S>(segments: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut pointer = String::new();
+    for segment in segments {
+        pointer.push('/');
+        pointer.push_str(&segment.as_ref().replace('~', "~0").replace('/', "~1"));
+    }
+    pointer
+}
+
+fn name_from_pointer(pointer: &str) -> String {
+    pointer
+        .trim_start_matches('#')
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Builder for a problem describing invalid request parameters.
+///
+/// The status defaults to `422 - Unprocessable Entity`.
+///
+/// #Example
+///
+/// ```rust
+/// use http_api_problem::*;
+///
+/// let problem = ValidationProblem::new()
+///     .status(StatusCode::BAD_REQUEST)
+///     .title("Your request parameters didn't validate.")
+///     .pointer("/age", "must be a positive integer")
+///     .param(InvalidParam::new("color", "must be 'green', 'red' or 'blue'").code("one_of"))
+///     .into_http_api_problem();
+///
+/// assert_eq!(Some(StatusCode::BAD_REQUEST), problem.status);
+///
+/// let params = problem.invalid_params().unwrap();
+/// assert_eq!(2, params.len());
+/// assert_eq!("age", params[0].name);
+/// assert_eq!(Some("one_of"), params[1].code.as_deref());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationProblem {
+    status: StatusCode,
+    title: Option<String>,
+    detail: Option<String>,
+    type_url: Option<String>,
+    instance: Option<String>,
+    invalid_params: Vec<InvalidParam>,
+}
+
+impl ValidationProblem {
+    /// Creates a new instance with the status `422 - Unprocessable Entity`
+    pub fn new() -> Self {
+        ValidationProblem {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            title: None,
+            detail: None,
+            type_url: None,
+            instance: None,
+            invalid_params: Vec::new(),
+        }
+    }
+
+    /// Sets the `status`
+    pub fn status<T: Into<StatusCode>>(mut self, status: T) -> Self {
+        self.status = status.into();
+        self
+    }
+
+    /// Sets the `title`
+    ///
+    /// If not set it will be derived from the status code.
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the `detail`
+    pub fn detail<T: Into<String>>(mut self, detail: T) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the `type_url`
+    ///
+    /// If not set it will be derived from the status code
+    /// (see [StatusTypeUrl]).
+    pub fn type_url<T: Into<String>>(mut self, type_url: T) -> Self {
+        self.type_url = Some(type_url.into());
+        self
+    }
+
+    /// Sets the `instance`
+    pub fn instance<T: Into<String>>(mut self, instance: T) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an [InvalidParam]
+    pub fn param(mut self, param: InvalidParam) -> Self {
+        self.push(param);
+        self
+    }
+
+    /// Adds an invalid member of the request body identified by
+    /// a [JSON Pointer](https://tools.ietf.org/html/rfc6901).
+    pub fn pointer<P: Into<String>, R: Into<String>>(self, pointer: P, reason: R) -> Self {
+        self.param(InvalidParam::at_pointer(pointer, reason))
+    }
+
+    /// Adds an invalid parameter which is not part of the request body,
+    /// e.g. a query parameter.
+    pub fn query<N: Into<String>, R: Into<String>>(self, name: N, reason: R) -> Self {
+        self.param(InvalidParam::new(name, reason))
+    }
+
+    /// Adds an [InvalidParam]
+    pub fn push(&mut self, param: InvalidParam) {
+        self.invalid_params.push(param);
+    }
+
+    /// Returns the collected [InvalidParam]s
+    pub fn invalid_params(&self) -> &[InvalidParam] {
+        &self.invalid_params
+    }
+
+    /// Returns `true` if no [InvalidParam] was added
+    pub fn is_empty(&self) -> bool {
+        self.invalid_params.is_empty()
+    }
+
+    /// Creates an [HttpApiProblem] containing the [InvalidParam]s
+    /// as the extension member `invalid-params`.
+    ///
+    /// The member is left out if there are no [InvalidParam]s.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let problem = ValidationProblem::new()
+    ///     .status(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    ///     .into_http_api_problem();
+    ///
+    /// assert_eq!(None, problem.json_value(INVALID_PARAMS_KEY));
+    /// ```
+    pub fn into_http_api_problem(self) -> HttpApiProblem {
+        let mut problem = HttpApiProblem::with_title_and_type(self.status);
+
+        if let Some(title) = self.title {
+            problem.title = Some(title);
+        }
+        if let Some(type_url) = self.type_url {
+            problem.type_url = Some(type_url);
+        }
+        problem.detail = self.detail;
+        problem.instance = self.instance;
+        if !self.invalid_params.is_empty() {
+            problem.set_value(INVALID_PARAMS_KEY, &self.invalid_params);
+        }
+
+        problem
+    }
+}
+
+impl Default for ValidationProblem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<InvalidParam> for ValidationProblem {
+    fn from_iter<T: IntoIterator<Item = InvalidParam>>(iter: T) -> Self {
+        let mut problem = ValidationProblem::new();
+        problem.invalid_params.extend(iter);
+        problem
+    }
+}
+
+impl From<ValidationProblem> for HttpApiProblem {
+    fn from(problem: ValidationProblem) -> Self {
+        problem.into_http_api_problem()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoApiError for ValidationProblem {
+    /// Creates an [ApiError] containing the [InvalidParam]s
+    /// as the field `invalid-params`.
+    ///
+    /// The field is left out if there are no [InvalidParam]s.
+    fn into_api_error(self) -> ApiError {
+        let mut builder = ApiError::builder(self.status);
+        if !self.invalid_params.is_empty() {
+            builder = builder.field(INVALID_PARAMS_KEY, &self.invalid_params);
+        }
+
+        builder.title = self.title;
+        builder.message = self.detail;
+        builder.type_url = self.type_url;
+        builder.instance = self.instance;
+
+        builder.finish()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl ApiError {
+    /// Returns the [InvalidParam]s of the field `invalid-params`.
+    ///
+    /// If there is no such field an empty `Vec` is returned.
+    /// Fails if the field can not be deserialized.
+    pub fn invalid_params(&self) -> Result<Vec<InvalidParam>, serde_json::Error> {
+        match self.fields().get(INVALID_PARAMS_KEY) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Returns the [InvalidParam]s of the extension member `invalid-params`.
+    ///
+    /// The member is looked up in the typed extension members first
+    /// and then in the [additional fields](HttpApiProblem::additional_fields).
+    /// If there is no such member an empty `Vec` is returned.
+    /// Fails if the member can not be deserialized.
+    pub fn invalid_params(&self) -> Result<Vec<InvalidParam>, serde_json::Error> {
+        if let Value::Object(mut typed) = serde_json::to_value(&self.extension_members)? {
+            if let Some(value) = typed.remove(INVALID_PARAMS_KEY) {
+                return serde_json::from_value(value);
+            }
+        }
+
+        match self.json_value(INVALID_PARAMS_KEY) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Conversions of a [Report](::garde::Report)
+//! of the [garde](https://crates.io/crates/garde) crate.
+use ::garde::error::Path;
+use ::garde::Report;
+use serde_json::Value;
+
+use super::*;
+
+impl From<&Report> for ValidationProblem {
+    /// Creates one [InvalidParam] per error of the report
+    /// located by a JSON Pointer.
+    ///
+    /// Requires the `garde` feature
+    fn from(report: &Report) -> Self {
+        report
+            .iter()
+            .map(|(path, error)| InvalidParam::at_pointer(pointer(path), error.message()))
+            .collect()
+    }
+}
+
+/// Creates a JSON Pointer from the components of the path.
+///
+/// Components without a key (e.g. of an `Option`) are skipped.
+fn pointer(path: &Path) -> String {
+    // garde only exposes the components of a path through its
+    // `Serialize` implementation: a sequence of `[kind, component]`
+    // pairs from the outermost to the innermost component.
+    let components = match serde_json::to_value(path) {
+        Ok(Value::Array(components)) => components,
+        _ => return json_pointer(std::iter::empty::<&str>()),
+    };
+
+    let segments =
+        components
+            .iter()
+            .filter_map(|component| match component.as_array().map(Vec::as_slice) {
+                Some([kind, Value::String(component)]) if kind != "none" => {
+                    Some(component.as_str())
+                }
+                _ => None,
+            });
+
+    json_pointer(segments)
+}
+
+impl From<Report> for ValidationProblem {
+    fn from(report: Report) -> Self {
+        ValidationProblem::from(&report)
+    }
+}
+
+impl From<Report> for HttpApiProblem {
+    fn from(report: Report) -> Self {
+        ValidationProblem::from(&report).into_http_api_problem()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoApiError for Report {
+    fn into_api_error(self) -> ApiError {
+        ValidationProblem::from(&self).into_api_error()
+    }
+}
@@ -0,0 +1,83 @@
+//! Conversions of [ValidationErrors](::validator::ValidationErrors)
+//! of the [validator](https://crates.io/crates/validator) crate.
+use std::collections::HashMap;
+
+use ::validator::{ValidationErrors, ValidationErrorsKind};
+
+use super::*;
+
+/// The key `validator` uses for errors of a whole struct
+const STRUCT_ERRORS_KEY: &str = "__all__";
+
+impl From<&ValidationErrors> for ValidationProblem {
+    /// Flattens nested struct and list errors into one [InvalidParam]
+    /// per error, located by a JSON Pointer.
+    ///
+    /// The `code` of the [InvalidParam] is the code of the validation
+    /// error. Errors of a whole struct (`__all__`) point to the struct itself.
+    ///
+    /// Requires the `validator` feature
+    fn from(errors: &ValidationErrors) -> Self {
+        let mut problem = ValidationProblem::new();
+        flatten(&mut problem, &mut Vec::new(), errors.errors());
+        problem
+    }
+}
+
+impl From<ValidationErrors> for ValidationProblem {
+    fn from(errors: ValidationErrors) -> Self {
+        ValidationProblem::from(&errors)
+    }
+}
+
+impl From<ValidationErrors> for HttpApiProblem {
+    fn from(errors: ValidationErrors) -> Self {
+        ValidationProblem::from(&errors).into_http_api_problem()
+    }
+}
+
+#[cfg(feature = "api-error")]
+impl IntoApiError for ValidationErrors {
+    fn into_api_error(self) -> ApiError {
+        ValidationProblem::from(&self).into_api_error()
+    }
+}
+
+fn flatten(
+    problem: &mut ValidationProblem,
+    path: &mut Vec<String>,
+    errors: &HashMap<&'static str, ValidationErrorsKind>,
+) {
+    let mut fields: Vec<_> = errors.iter().collect();
+    fields.sort_by_key(|(field, _)| *field);
+
+    for (field, kind) in fields {
+        let is_struct_error = *field == STRUCT_ERRORS_KEY;
+        if !is_struct_error {
+            path.push(field.to_string());
+        }
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    problem.push(
+                        InvalidParam::at_pointer(json_pointer(path.iter()), error.to_string())
+                            .code(error.code.clone()),
+                    );
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => flatten(problem, path, errors.errors()),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    path.push(index.to_string());
+                    flatten(problem, path, errors.errors());
+                    path.pop();
+                }
+            }
+        }
+
+        if !is_struct_error {
+            path.pop();
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Recovering [warp] rejections as problems
+//!
+//! [recover] can be passed to `Filter::recover`. It responds with
+//!
+//! * an [HttpApiProblem] or `ApiError` (with the feature `api-error`)
+//!   which was used to reject a request
+//! * a problem for warp's own rejections with the same status
+//!   warp would respond with
+//! * `500 - Internal Server Error` for all other rejections
+//!
+//! #Example
+//!
+//! ```rust
+//! use http_api_problem::*;
+//! use warp::Filter;
+//!
+//! async fn get_order(id: u32) -> Result<String, warp::Rejection> {
+//!     Err(warp::reject::custom(
+//!         HttpApiProblem::new(StatusCode::NOT_FOUND).detail(format!("no order {}", id)),
+//!     ))
+//! }
+//!
+//! let routes = warp::path!("orders" / u32)
+//!     .and_then(get_order)
+//!     .recover(recover);
+//! ```
+use std::convert::Infallible;
+use std::error::Error;
+
+use warp::filters::body::BodyDeserializeError;
+use warp::reject::{
+    InvalidHeader, InvalidQuery, LengthRequired, MethodNotAllowed, MissingCookie, MissingHeader,
+    PayloadTooLarge, UnsupportedMediaType,
+};
+use warp::Rejection;
+
+use super::*;
+
+/// Responds to a [Rejection] with a problem
+///
+/// To be used with `Filter::recover`.
+///
+/// Requires the `warp` feature
+pub async fn recover(rejection: Rejection) -> Result<warp::reply::Response, Infallible> {
+    Ok(rejection_to_problem(&rejection).to_warp_response())
+}
+
+/// Creates an [HttpApiProblem] for a [Rejection]
+///
+/// An [HttpApiProblem] or `ApiError` the request was rejected
+/// with is returned as it is.
+///
+/// Requires the `warp` feature
+pub fn rejection_to_problem(rejection: &Rejection) -> HttpApiProblem {
+    if let Some(problem) = rejection.find::<HttpApiProblem>() {
+        return problem.clone();
+    }
+
+    #[cfg(feature = "api-error")]
+    if let Some(error) = rejection.find::<ApiError>() {
+        return error.to_http_api_problem();
+    }
+
+    if rejection.is_not_found() {
+        return HttpApiProblem::with_title_and_type(StatusCode::NOT_FOUND);
+    }
+
+    if let Some(err) = rejection.find::<MethodNotAllowed>() {
+        return known_problem(StatusCode::METHOD_NOT_ALLOWED, err).into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<MissingHeader>() {
+        return known_problem(StatusCode::BAD_REQUEST, err)
+            .query(err.name(), "missing")
+            .into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<InvalidHeader>() {
+        return known_problem(StatusCode::BAD_REQUEST, err)
+            .query(err.name(), "invalid")
+            .into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<MissingCookie>() {
+        return known_problem(StatusCode::BAD_REQUEST, err)
+            .query(err.name(), "missing")
+            .into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<InvalidQuery>() {
+        return known_problem(StatusCode::BAD_REQUEST, err).into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<BodyDeserializeError>() {
+        let problem = known_problem(StatusCode::BAD_REQUEST, err);
+        let param = err
+            .source()
+            .and_then(|source| InvalidParam::from_serde_message(&source.to_string()));
+        return match param {
+            Some(param) => problem.param(param),
+            None => problem,
+        }
+        .into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<LengthRequired>() {
+        return known_problem(StatusCode::LENGTH_REQUIRED, err).into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<PayloadTooLarge>() {
+        return known_problem(StatusCode::PAYLOAD_TOO_LARGE, err).into_http_api_problem();
+    }
+    if let Some(err) = rejection.find::<UnsupportedMediaType>() {
+        return known_problem(StatusCode::UNSUPPORTED_MEDIA_TYPE, err).into_http_api_problem();
+    }
+
+    HttpApiProblem::with_title_and_type(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn known_problem<E: fmt::Display>(status: StatusCode, err: &E) -> ValidationProblem {
+    ValidationProblem::new()
+        .status(status)
+        .detail(err.to_string())
+}
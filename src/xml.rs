@@ -0,0 +1,517 @@
+//! The XML representation of a problem as described in
+//! [RFC9457, Appendix B](https://www.rfc-editor.org/rfc/rfc9457#appendix-B).
+//!
+//! # Example
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <problem xmlns="urn:ietf:rfc:7807">
+//!   <type>https://example.com/probs/out-of-credit</type>
+//!   <title>You do not have enough credit.</title>
+//!   <balance>30</balance>
+//!   <accounts type="array">
+//!     <i>https://example.net/account/12345</i>
+//!     <i>https://example.net/account/67890</i>
+//!   </accounts>
+//! </problem>
+//! ```
+//!
+//! Extension members are encoded as child elements of `problem`.
+//! Arrays become a sequence of `i` elements and objects become
+//! nested elements. Members whose names are not valid XML names
+//! can not be represented and are omitted.
+//!
+//! Since XML does not carry type information the text of
+//! extension members which looks like a number or a boolean
+//! will be parsed as such. Text is kept verbatim, including
+//! leading and trailing whitespace. `null` is encoded as an element with
+//! `xsi:nil="true"` and arrays and objects as elements with
+//! the attribute `type="array"` or `type="object"`. Elements without
+//! this attribute are parsed as an array if all of their children
+//! are named `i` and as an object otherwise.
+//!
+//! Characters which are not allowed in XML 1.0 (most control
+//! characters) are replaced by `U+FFFD`.
+//!
+//! Documents nested deeper than 128 elements, including the root
+//! element, are rejected.
+//!
+//! Only documents whose root element is `problem` in the namespace
+//! [PROBLEM_XML_NAMESPACE] are accepted.
+use std::error::Error;
+use std::fmt;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::NsReader;
+use serde_json::{Map, Number, Value};
+
+use super::*;
+
+/// The XML namespace of a problem document
+///
+/// "urn:ietf:rfc:7807"
+pub static PROBLEM_XML_NAMESPACE: &str = "urn:ietf:rfc:7807";
+
+/// The namespace of the `xsi:nil` attribute marking `null` values
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// The maximum nesting depth of elements accepted while parsing
+///
+/// The parsed tree is converted recursively, so this keeps deeply
+/// nested documents from overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// An error which occurred while reading a problem from XML
+#[derive(Debug)]
+pub struct XmlError {
+    message: String,
+}
+
+impl XmlError {
+    fn new<T: Into<String>>(message: T) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid problem XML: {}", self.message)
+    }
+}
+
+impl Error for XmlError {}
+
+impl From<quick_xml::Error> for XmlError {
+    fn from(error: quick_xml::Error) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+impl<Ext: Serialize> HttpApiProblem<Ext> {
+    /// Serialize to an XML `Vec<u8>`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the typed extension members do not serialize to a map.
+    ///
+    /// Requires the `xml` feature
+    pub fn xml_bytes(&self) -> Vec<u8> {
+        self.xml_string().into_bytes()
+    }
+
+    /// Serialize to an XML `String`
+    ///
+    /// Extension members whose names are not valid XML names are omitted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the typed extension members do not serialize to a map.
+    /// Use [HttpApiProblem::try_xml_string] to handle this case.
+    ///
+    /// Requires the `xml` feature
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let p = HttpApiProblem::new(StatusCode::FORBIDDEN)
+    ///     .title("You do not have enough credit.")
+    ///     .value("balance", &30);
+    ///
+    /// let xml = p.xml_string();
+    ///
+    /// assert!(xml.contains("<problem xmlns=\"urn:ietf:rfc:7807\">"));
+    /// assert!(xml.contains("<status>403</status>"));
+    /// assert!(xml.contains("<balance>30</balance>"));
+    /// ```
+    pub fn xml_string(&self) -> String {
+        self.try_xml_string().unwrap()
+    }
+
+    /// Serialize to an XML `String`
+    ///
+    /// Fails if the typed extension members do not serialize to a map.
+    ///
+    /// Requires the `xml` feature
+    pub fn try_xml_string(&self) -> Result<String, serde_json::Error> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<problem xmlns=\"{}\">", PROBLEM_XML_NAMESPACE));
+
+        if let Some(type_url) = self.type_url.as_deref() {
+            write_text_element(&mut xml, "type", type_url);
+        }
+        if let Some(status) = self.status {
+            write_text_element(&mut xml, "status", status.as_str());
+        }
+        if let Some(title) = self.title.as_deref() {
+            write_text_element(&mut xml, "title", title);
+        }
+        if let Some(detail) = self.detail.as_deref() {
+            write_text_element(&mut xml, "detail", detail);
+        }
+        if let Some(instance) = self.instance.as_deref() {
+            write_text_element(&mut xml, "instance", instance);
+        }
+
+        let mut members: Vec<_> = self.extension_members_json()?.into_iter().collect();
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, value) in members {
+            if is_xml_name(&name) {
+                write_value_element(&mut xml, &name, &value);
+            }
+        }
+
+        xml.push_str("</problem>");
+        Ok(xml)
+    }
+}
+
+impl HttpApiProblem {
+    /// Parse a problem from its XML representation
+    ///
+    /// An invalid `status` results in the `status` being `None`
+    /// as it is the case when deserializing from JSON.
+    ///
+    /// Requires the `xml` feature
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// use http_api_problem::*;
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <problem xmlns="urn:ietf:rfc:7807">
+    ///   <type>https://example.com/probs/out-of-credit</type>
+    ///   <status>403</status>
+    ///   <balance>30</balance>
+    ///   <accounts type="array">
+    ///     <i>https://example.net/account/12345</i>
+    ///     <i>https://example.net/account/67890</i>
+    ///   </accounts>
+    /// </problem>"#;
+    ///
+    /// let p = HttpApiProblem::from_xml_str(xml).unwrap();
+    ///
+    /// assert_eq!(Some(StatusCode::FORBIDDEN), p.status);
+    /// assert_eq!(Some("https://example.com/probs/out-of-credit"), p.type_url.as_deref());
+    /// assert_eq!(Some(30), p.get_value::<String, u32>("balance"));
+    /// assert_eq!(
+    ///     Some(vec![
+    ///         "https://example.net/account/12345".to_string(),
+    ///         "https://example.net/account/67890".to_string()
+    ///     ]),
+    ///     p.get_value::<String, Vec<String>>("accounts")
+    /// );
+    /// ```
+    pub fn from_xml_str(xml: &str) -> Result<Self, XmlError> {
+        let root = parse_document(xml)?;
+
+        if root.name != "problem" {
+            return Err(XmlError::new(format!(
+                "expected root element 'problem' but found '{}'",
+                root.name
+            )));
+        }
+
+        if root.namespace.as_deref() != Some(PROBLEM_XML_NAMESPACE) {
+            return Err(XmlError::new(format!(
+                "expected the namespace '{}' but found {:?}",
+                PROBLEM_XML_NAMESPACE, root.namespace
+            )));
+        }
+
+        let mut problem = HttpApiProblem::empty();
+        for child in root.children {
+            match child.name.as_str() {
+                "type" => problem.type_url = Some(child.text),
+                "status" => {
+                    problem.status = child
+                        .text
+                        .trim()
+                        .parse::<u16>()
+                        .ok()
+                        .and_then(|s| StatusCode::from_u16(s).ok())
+                }
+                "title" => problem.title = Some(child.text),
+                "detail" => problem.detail = Some(child.text),
+                "instance" => problem.instance = Some(child.text),
+                _ => {
+                    let name = child.name.clone();
+                    problem.additional_fields.insert(name, child.into_value());
+                }
+            }
+        }
+
+        Ok(problem)
+    }
+
+    /// Parse a problem from its XML representation
+    ///
+    /// Requires the `xml` feature
+    pub fn from_xml_slice(xml: &[u8]) -> Result<Self, XmlError> {
+        let xml = std::str::from_utf8(xml).map_err(|err| XmlError::new(err.to_string()))?;
+        Self::from_xml_str(xml)
+    }
+}
+
+fn write_text_element(xml: &mut String, name: &str, text: &str) {
+    xml.push('<');
+    xml.push_str(name);
+    xml.push('>');
+    escape_text(xml, text);
+    xml.push_str("</");
+    xml.push_str(name);
+    xml.push('>');
+}
+
+fn write_value_element(xml: &mut String, name: &str, value: &Value) {
+    match value {
+        Value::Null => {
+            xml.push('<');
+            xml.push_str(name);
+            xml.push_str(&format!(
+                " xmlns:xsi=\"{}\" xsi:nil=\"true\"/>",
+                XSI_NAMESPACE
+            ));
+        }
+        Value::Array(items) if items.is_empty() => {
+            xml.push('<');
+            xml.push_str(name);
+            xml.push_str(" type=\"array\"/>");
+        }
+        Value::Bool(b) => write_text_element(xml, name, &b.to_string()),
+        Value::Number(n) => write_text_element(xml, name, &n.to_string()),
+        Value::String(s) => write_text_element(xml, name, s),
+        Value::Array(items) => {
+            xml.push('<');
+            xml.push_str(name);
+            xml.push_str(" type=\"array\">");
+            for item in items {
+                write_value_element(xml, "i", item);
+            }
+            xml.push_str("</");
+            xml.push_str(name);
+            xml.push('>');
+        }
+        Value::Object(members) => {
+            let members: Vec<_> = members.iter().filter(|(key, _)| is_xml_name(key)).collect();
+            if members.is_empty() {
+                xml.push('<');
+                xml.push_str(name);
+                xml.push_str(" type=\"object\"/>");
+                return;
+            }
+
+            xml.push('<');
+            xml.push_str(name);
+            xml.push_str(" type=\"object\">");
+            for (key, value) in members {
+                write_value_element(xml, key, value);
+            }
+            xml.push_str("</");
+            xml.push_str(name);
+            xml.push('>');
+        }
+    }
+}
+
+/// Escapes the markup characters and `\r`, which a parser would
+/// normalize, and replaces characters not allowed in XML 1.0
+fn escape_text(xml: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '&' => xml.push_str("&amp;"),
+            '\r' => xml.push_str("&#xD;"),
+            c if is_xml_char(c) => xml.push(c),
+            _ => xml.push(char::REPLACEMENT_CHARACTER),
+        }
+    }
+}
+
+/// Checks for a character allowed in XML 1.0
+fn is_xml_char(c: char) -> bool {
+    matches!(c,
+        '\t' | '\n' | '\r'
+        | '\u{20}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}')
+}
+
+/// Checks for a valid XML name without a namespace prefix.
+///
+/// Only ASCII is checked strictly. Non ASCII alphanumerics are
+/// accepted as the XML specification allows most of them.
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let first_ok = match chars.next() {
+        Some(c) => c == '_' || c.is_alphabetic(),
+        None => false,
+    };
+
+    first_ok
+        && !name
+            .get(..3)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("xml"))
+        && chars.all(|c| c == '_' || c == '-' || c == '.' || c.is_alphanumeric())
+}
+
+struct Element {
+    name: String,
+    namespace: Option<String>,
+    text: String,
+    children: Vec<Element>,
+    /// The kind of value as marked by the attributes
+    kind: Option<Kind>,
+}
+
+/// A kind of value that can not be derived from the text
+enum Kind {
+    Null,
+    Array,
+    Object,
+}
+
+impl Element {
+    fn new(reader: &NsReader<&[u8]>, start: &BytesStart) -> Result<Self, XmlError> {
+        let namespace = match reader.resolve_element(start.name()).0 {
+            ResolveResult::Bound(Namespace(ns)) => Some(String::from_utf8_lossy(ns).into_owned()),
+            _ => None,
+        };
+
+        let mut kind = None;
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|err| XmlError::new(err.to_string()))?;
+            let value = attribute.unescape_value()?;
+            match reader.resolve_attribute(attribute.key) {
+                (ResolveResult::Bound(Namespace(ns)), local)
+                    if ns == XSI_NAMESPACE.as_bytes()
+                        && local.as_ref() == b"nil"
+                        && value == "true" =>
+                {
+                    kind = Some(Kind::Null);
+                }
+                (ResolveResult::Unbound, local) if local.as_ref() == b"type" => {
+                    match value.as_ref() {
+                        "array" => kind = Some(Kind::Array),
+                        "object" => kind = Some(Kind::Object),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            namespace,
+            text: String::new(),
+            children: Vec::new(),
+            kind,
+        })
+    }
+
+    fn into_value(self) -> Value {
+        let is_array = match self.kind {
+            Some(Kind::Array) => true,
+            Some(Kind::Object) => false,
+            Some(Kind::Null) if self.children.is_empty() && self.text.is_empty() => {
+                return Value::Null
+            }
+            _ if self.children.is_empty() => return text_to_value(self.text),
+            _ => self.children.iter().all(|c| c.name == "i"),
+        };
+
+        if is_array {
+            Value::Array(self.children.into_iter().map(|c| c.into_value()).collect())
+        } else {
+            let mut members = Map::new();
+            for child in self.children {
+                let name = child.name.clone();
+                members.insert(name, child.into_value());
+            }
+            Value::Object(members)
+        }
+    }
+}
+
+fn text_to_value(text: String) -> Value {
+    match text.as_str() {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(n) = text.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+
+    if let Some(n) = text.parse::<f64>().ok().and_then(Number::from_f64) {
+        if text
+            .chars()
+            .all(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+        {
+            return Value::Number(n);
+        }
+    }
+
+    Value::String(text)
+}
+
+fn parse_document(xml: &str) -> Result<Element, XmlError> {
+    let mut reader = NsReader::from_str(xml);
+
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        let event = reader.read_event()?;
+        if matches!(event, Event::Start(_) | Event::Empty(_)) && stack.len() >= MAX_DEPTH {
+            return Err(XmlError::new(format!(
+                "elements are nested deeper than {} levels",
+                MAX_DEPTH
+            )));
+        }
+
+        match event {
+            Event::Start(start) => {
+                let element = Element::new(&reader, &start)?;
+                stack.push(element);
+            }
+            Event::Empty(start) => {
+                let element = Element::new(&reader, &start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    current
+                        .text
+                        .push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| XmlError::new("unexpected closing tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Eof => return Err(XmlError::new("unexpected end of document")),
+            _ => {}
+        }
+    }
+}